@@ -0,0 +1,244 @@
+use serde_json::json;
+
+use crate::day5::{
+    CrateMoveError, CrateMover, ElfCrateMove, ElfCrateMoves, ElfCrateStacks, MoveMode,
+};
+use crate::visualize::render_crate_stacks_highlighted;
+use crate::Result;
+
+/// Every state the stacks pass through while a crane works through a move
+/// list, with a cursor for stepping back and forth between them.
+///
+/// Frame 0 is the starting stacks and frame N is the stacks after move N.
+pub struct CrateAnimation {
+    frames: Vec<ElfCrateStacks>,
+    moves: Vec<ElfCrateMove>,
+    warnings: Vec<CrateMoveError>,
+    position: usize,
+}
+
+impl CrateAnimation {
+    /// Plays every move through `crane`. In strict mode the first invalid
+    /// move is an error; in lenient mode it is kept as a warning on its frame.
+    pub fn new(
+        stacks: &ElfCrateStacks,
+        moves: &ElfCrateMoves,
+        crane: &dyn CrateMover,
+        mode: MoveMode,
+    ) -> Result<Self, CrateMoveError> {
+        // Frames are snapshots, so they don't carry an undo journal.
+        let mut start = stacks.clone();
+        start.clear_journal();
+        let mut frames = vec![start];
+        let mut warnings = vec![];
+        for (idx, elf_move) in moves.moves().iter().enumerate() {
+            let mut next = frames[frames.len() - 1].clone();
+            warnings.extend(next.make_move(idx + 1, elf_move, crane, mode)?);
+            frames.push(next);
+        }
+
+        Ok(Self {
+            frames,
+            moves: moves.moves().to_vec(),
+            warnings,
+            position: 0,
+        })
+    }
+
+    /// The invalid moves that were worked around in lenient mode.
+    pub fn warnings(&self) -> &[CrateMoveError] {
+        &self.warnings
+    }
+
+    /// Number of frames, one more than the number of moves.
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// The move the cursor is at, 0 being the starting stacks.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn stacks(&self) -> &ElfCrateStacks {
+        &self.frames[self.position]
+    }
+
+    /// Steps to the next move. Returns false when already at the end.
+    pub fn forward(&mut self) -> bool {
+        if self.position + 1 >= self.frames.len() {
+            return false;
+        }
+        self.position += 1;
+        true
+    }
+
+    /// Steps back one move. Returns false when already at the start.
+    pub fn back(&mut self) -> bool {
+        if self.position == 0 {
+            return false;
+        }
+        self.position -= 1;
+        true
+    }
+
+    /// Moves the cursor to move `n`, stopping at the last move if there
+    /// aren't that many.
+    pub fn jump(&mut self, n: usize) -> usize {
+        self.position = n.min(self.frames.len() - 1);
+        self.position
+    }
+
+    /// How many crates move `n` really took, fewer than it asked for when
+    /// it was worked around in lenient mode.
+    fn moved(&self, n: usize) -> usize {
+        let elf_move = &self.moves[n - 1];
+        if elf_move.from_stack() == elf_move.to_stack() {
+            return 0;
+        }
+        let height = |frame: &ElfCrateStacks| {
+            let stack = frame.stacks().get(elf_move.from_stack().wrapping_sub(1));
+            stack.map_or(0, Vec::len)
+        };
+        height(&self.frames[n - 1]).saturating_sub(height(&self.frames[n]))
+    }
+
+    /// Draws frame `n` with a caption naming the move that produced it and
+    /// the crates it moved highlighted when `color` is set.
+    pub fn render_frame(&self, n: usize, color: bool) -> String {
+        let n = n.min(self.frames.len() - 1);
+        let (mut caption, highlight) = match n.checked_sub(1).map(|idx| &self.moves[idx]) {
+            Some(elf_move) => (
+                format!("Move {}/{}: {}", n, self.moves.len(), elf_move),
+                Some((elf_move.to_stack(), self.moved(n))),
+            ),
+            None => (
+                format!("Move 0/{}: starting stacks", self.moves.len()),
+                None,
+            ),
+        };
+
+        if let Some(warning) = self.warnings.iter().find(|warning| warning.index() == n) {
+            caption = format!("{} (invalid, {})", caption, warning);
+        }
+
+        format!(
+            "{}\n{}",
+            caption,
+            render_crate_stacks_highlighted(&self.frames[n], highlight, color)
+        )
+    }
+
+    pub fn render(&self, color: bool) -> String {
+        self.render_frame(self.position, color)
+    }
+
+    /// Every frame without colour, separated by blank lines.
+    pub fn to_text(&self) -> String {
+        (0..self.frames.len())
+            .map(|n| self.render_frame(n, false))
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Every frame as an asciicast v2 recording, `delay` seconds apart.
+    pub fn to_asciicast(&self, delay: f64) -> Result<String> {
+        let frames: Vec<String> = (0..self.frames.len())
+            .map(|n| self.render_frame(n, true))
+            .collect();
+        // Colour codes take no room on screen, so measure the plain frames.
+        let width = (0..self.frames.len())
+            .map(|n| self.render_frame(n, false))
+            .filter_map(|frame| frame.lines().map(|line| line.chars().count()).max())
+            .max()
+            .unwrap_or(0);
+        let height = frames
+            .iter()
+            .map(|frame| frame.lines().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![serde_json::to_string(&json!({
+            "version": 2,
+            "width": width.max(1),
+            "height": height.max(1),
+        }))?];
+        for (n, frame) in frames.iter().enumerate() {
+            let screen = format!("\x1b[2J\x1b[H{}", frame.replace('\n', "\r\n"));
+            lines.push(serde_json::to_string(&json!([
+                n as f64 * delay,
+                "o",
+                screen
+            ]))?);
+        }
+        Ok(lines.join("\n") + "\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrateAnimation;
+    use crate::day5::{CrateMover9000, CrateMover9001, ElfCrateMoves, ElfCrateStacks, MoveMode};
+
+    fn example() -> CrateAnimation {
+        let stacks: ElfCrateStacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 "
+            .parse()
+            .unwrap();
+        let moves: ElfCrateMoves =
+            "move 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2"
+                .parse()
+                .unwrap();
+        CrateAnimation::new(&stacks, &moves, &CrateMover9000, MoveMode::Strict).unwrap()
+    }
+
+    #[test]
+    fn it_steps_forward_and_back_through_moves() {
+        let mut animation = example();
+
+        assert_eq!(animation.len(), 5);
+        assert!(!animation.back());
+        assert!(animation.forward());
+        assert_eq!(
+            animation.render(false),
+            "Move 1/4: move 1 from 2 to 1\n[D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 "
+        );
+
+        assert_eq!(animation.jump(10), 4);
+        assert_eq!(animation.stacks().journal_len(), 0);
+        assert!(!animation.forward());
+        assert_eq!(animation.stacks().get_tops_of_stacks().to_string(), "CMZ");
+        assert!(animation.back());
+        assert_eq!(animation.position(), 3);
+    }
+
+    #[test]
+    fn it_records_an_asciicast() {
+        let cast = example().to_asciicast(0.5).unwrap();
+        let lines: Vec<&str> = cast.lines().collect();
+
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].contains("\"version\":2"));
+        assert!(lines[0].contains("\"width\":28"));
+        assert!(lines[2].starts_with("[0.5,\"o\","));
+    }
+
+    #[test]
+    fn it_flags_invalid_moves_in_lenient_mode() {
+        let stacks: ElfCrateStacks = "[A]\n 1   2 ".parse().unwrap();
+        let moves: ElfCrateMoves = "move 2 from 1 to 2".parse().unwrap();
+
+        assert!(CrateAnimation::new(&stacks, &moves, &CrateMover9001, MoveMode::Strict).is_err());
+
+        let animation =
+            CrateAnimation::new(&stacks, &moves, &CrateMover9001, MoveMode::Lenient).unwrap();
+        assert_eq!(animation.warnings().len(), 1);
+        assert_eq!(animation.moved(1), 1);
+        assert!(animation
+            .render_frame(1, false)
+            .starts_with("Move 1/1: move 2 from 1 to 2 (invalid, move 1: can't take 2"));
+    }
+}
@@ -0,0 +1,267 @@
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Result, Unsolved};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct BenchConfig {
+    pub warmup: usize,
+    pub iterations: usize,
+}
+
+impl Default for BenchConfig {
+    fn default() -> Self {
+        Self {
+            warmup: 3,
+            iterations: 20,
+        }
+    }
+}
+
+/// Summary of one phase's samples, in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseStats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+}
+
+impl PhaseStats {
+    /// Nearest-rank statistics over the samples.
+    pub fn from_samples(samples: &mut [Duration]) -> Self {
+        assert!(!samples.is_empty(), "Need at least one sample");
+        samples.sort();
+        let rank = |pct: usize| {
+            let idx = (pct * samples.len()).div_ceil(100).max(1) - 1;
+            samples[idx].as_nanos() as u64
+        };
+        Self {
+            min_ns: samples[0].as_nanos() as u64,
+            median_ns: rank(50),
+            p95_ns: rank(95),
+        }
+    }
+}
+
+impl fmt::Display for PhaseStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "min {:>10} | median {:>10} | p95 {:>10}",
+            format_ns(self.min_ns),
+            format_ns(self.median_ns),
+            format_ns(self.p95_ns)
+        )
+    }
+}
+
+pub fn format_ns(ns: u64) -> String {
+    match ns {
+        0..=999 => format!("{}ns", ns),
+        1_000..=999_999 => format!("{:.1}µs", ns as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.1}ms", ns as f64 / 1e6),
+        _ => format!("{:.2}s", ns as f64 / 1e9),
+    }
+}
+
+/// Times `phase` after a warmup. The result of every call is passed through
+/// `black_box` so the work cannot be optimised away.
+pub fn time_phase<T>(
+    config: &BenchConfig,
+    mut phase: impl FnMut() -> Result<T>,
+) -> Result<PhaseStats> {
+    for _ in 0..config.warmup {
+        black_box(phase()?);
+    }
+
+    let mut samples = Vec::with_capacity(config.iterations.max(1));
+    for _ in 0..config.iterations.max(1) {
+        let start = Instant::now();
+        let output = phase()?;
+        samples.push(start.elapsed());
+        black_box(output);
+    }
+    Ok(PhaseStats::from_samples(&mut samples))
+}
+
+/// Like [`time_phase`], but an [`Unsolved`] part is reported as `None`
+/// rather than as an error.
+pub fn time_part<T>(
+    config: &BenchConfig,
+    phase: impl FnMut() -> Result<T>,
+) -> Result<Option<PhaseStats>> {
+    match time_phase(config, phase) {
+        Ok(stats) => Ok(Some(stats)),
+        Err(err) if err.is::<Unsolved>() => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DayTimings {
+    pub parse: PhaseStats,
+    pub part1: Option<PhaseStats>,
+    pub part2: Option<PhaseStats>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchResult {
+    pub name: String,
+    pub day: u8,
+    pub variant: String,
+    #[serde(flatten)]
+    pub timings: DayTimings,
+}
+
+impl BenchResult {
+    fn phases(&self) -> [(&'static str, Option<&PhaseStats>); 3] {
+        [
+            ("parse", Some(&self.timings.parse)),
+            ("part1", self.timings.part1.as_ref()),
+            ("part2", self.timings.part2.as_ref()),
+        ]
+    }
+}
+
+/// The JSON document written by `aoc bench --json` and read back as a
+/// baseline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BenchReport {
+    pub config: BenchConfig,
+    pub results: Vec<BenchResult>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Regression {
+    pub name: String,
+    pub variant: String,
+    pub phase: &'static str,
+    pub baseline_ns: u64,
+    pub current_ns: u64,
+}
+
+impl Regression {
+    pub fn slowdown(&self) -> f64 {
+        self.current_ns as f64 / self.baseline_ns.max(1) as f64
+    }
+}
+
+impl fmt::Display for Regression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}) {}: median {} -> {} ({:+.1}%)",
+            self.name,
+            self.variant,
+            self.phase,
+            format_ns(self.baseline_ns),
+            format_ns(self.current_ns),
+            (self.slowdown() - 1.0) * 100.0
+        )
+    }
+}
+
+/// Flags every phase whose median got slower than the baseline by more
+/// than `threshold`, where `0.1` means 10%.
+pub fn find_regressions(
+    baseline: &BenchReport,
+    current: &BenchReport,
+    threshold: f64,
+) -> Vec<Regression> {
+    let mut regressions = vec![];
+    for result in current.results.iter() {
+        let Some(base) = baseline
+            .results
+            .iter()
+            .find(|base| base.name == result.name && base.variant == result.variant)
+        else {
+            continue;
+        };
+
+        for ((phase, current_stats), (_, base_stats)) in
+            result.phases().into_iter().zip(base.phases())
+        {
+            if let (Some(current_stats), Some(base_stats)) = (current_stats, base_stats) {
+                let regression = Regression {
+                    name: result.name.clone(),
+                    variant: result.variant.clone(),
+                    phase,
+                    baseline_ns: base_stats.median_ns,
+                    current_ns: current_stats.median_ns,
+                };
+                if regression.slowdown() > 1.0 + threshold {
+                    regressions.push(regression);
+                }
+            }
+        }
+    }
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{find_regressions, BenchConfig, BenchReport, BenchResult, DayTimings, PhaseStats};
+
+    fn stats(median_ns: u64) -> PhaseStats {
+        PhaseStats {
+            min_ns: median_ns,
+            median_ns,
+            p95_ns: median_ns,
+        }
+    }
+
+    fn report(parse_ns: u64, part1_ns: u64) -> BenchReport {
+        BenchReport {
+            config: BenchConfig::default(),
+            results: vec![BenchResult {
+                name: String::from("day1"),
+                day: 1,
+                variant: String::from("puzzle"),
+                timings: DayTimings {
+                    parse: stats(parse_ns),
+                    part1: Some(stats(part1_ns)),
+                    part2: None,
+                },
+            }],
+        }
+    }
+
+    #[test]
+    fn it_computes_nearest_rank_stats() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_nanos).collect();
+        let stats = PhaseStats::from_samples(&mut samples);
+
+        assert_eq!(
+            stats,
+            PhaseStats {
+                min_ns: 1,
+                median_ns: 10,
+                p95_ns: 19
+            }
+        );
+    }
+
+    #[test]
+    fn it_flags_phases_slower_than_the_threshold() {
+        let baseline = report(1000, 1000);
+        let current = report(1050, 2000);
+
+        let regressions = find_regressions(&baseline, &current, 0.1);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].phase, "part1");
+    }
+
+    #[test]
+    fn it_round_trips_reports_through_json() {
+        let json = serde_json::to_string(&report(1, 2)).unwrap();
+        let parsed: BenchReport = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.results[0].timings.part1, Some(stats(2)));
+        assert_eq!(parsed.results[0].timings.part2, None);
+    }
+}
@@ -0,0 +1,936 @@
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, Context};
+use clap::{Args, Parser, Subcommand, ValueEnum};
+
+use aoc_2022::{
+    animate::CrateAnimation,
+    bench::{
+        find_regressions, format_ns, time_phase, BenchConfig, BenchReport, BenchResult, PhaseStats,
+    },
+    cleanup::{plan_cleanup, CleanupConfig},
+    compact::{generate, CompactCrane, SyntheticConfig},
+    day5::{
+        crate_mover, CrateMover, CrateMover9000, CrateMover9001, Day5, ElfCrateStacks, MoveMode,
+        OneByOneCrateMover9000,
+    },
+    day6::{MarkerDecoder, StripFinalNewline, SOM_SIZE, SOP_SIZE},
+    diagnostic::ParseError,
+    elf_fs::{parse_transcript, process_efs, replay, ReplayPolicy},
+    elf_path::ElfPath,
+    elf_report::{du_table, ncdu_export, tree, DuOrder},
+    elf_shell::{ElfShell, HELP},
+    elf_transcript::{read_dir_tree, to_transcript, TranscriptConfig, TraversalOrder},
+    input::{InputResolver, InputSource, LoadedInput, DEFAULT_VARIANT, STDIN_PATH},
+    planner::{plan_moves, PlanConfig},
+    registry::{registry, Part, RegisteredDay},
+    verify::{verify_day, Answers, Verdict},
+    Error, Result, Solution, Unsolved,
+};
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportFormat {
+    Tree,
+    Du,
+    Ncdu,
+}
+
+/// Advent of Code 2022 solutions runner.
+#[derive(Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Input file, repeated for days with several files; `-` reads stdin
+    #[arg(long = "input", conflicts_with = "variant")]
+    inputs: Vec<PathBuf>,
+
+    /// Manifest variant to load, such as `example` or `puzzle`
+    #[arg(long)]
+    variant: Option<String>,
+
+    /// Manifest to resolve variants from [default: $AOC_INPUT_DIR/manifest.toml]
+    #[arg(long)]
+    manifest: Option<PathBuf>,
+}
+
+impl InputArgs {
+    fn resolver(&self) -> Result<InputResolver> {
+        resolver(self.manifest.as_deref())
+    }
+
+    fn source(&self) -> InputSource {
+        match &self.variant {
+            _ if !self.inputs.is_empty() => InputSource::Files(self.inputs.clone()),
+            Some(variant) => InputSource::Variant(variant.clone()),
+            None => InputSource::default(),
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run one day's solution, or every registered solution with --all
+    Run {
+        /// Day of the calendar to run
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u8>,
+
+        /// Only run this part (1 or 2)
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+        part: Option<u8>,
+
+        /// Run every registered solution
+        #[arg(long)]
+        all: bool,
+
+        /// Pick a specific solution by name when a day has several
+        #[arg(long)]
+        solution: Option<String>,
+
+        /// Draw the puzzle in the terminal before the answers, for days that
+        /// support it
+        #[arg(long)]
+        visualize: bool,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Check solutions against the known answers in answers.toml
+    Verify {
+        /// Only verify this day
+        day: Option<u8>,
+
+        /// Only verify the solution with this name
+        #[arg(long)]
+        solution: Option<String>,
+
+        /// Manifest to resolve variants from; answers.toml is read from the
+        /// same directory
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Time parsing and each part of every selected solution
+    Bench {
+        /// Only benchmark this day
+        day: Option<u8>,
+
+        /// Only benchmark the solution with this name
+        #[arg(long)]
+        solution: Option<String>,
+
+        /// Manifest variant to benchmark against
+        #[arg(long, default_value = DEFAULT_VARIANT)]
+        variant: String,
+
+        /// Manifest to resolve variants from
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+
+        /// Timed iterations per phase
+        #[arg(long, default_value_t = BenchConfig::default().iterations)]
+        iterations: usize,
+
+        /// Untimed iterations per phase before measuring
+        #[arg(long, default_value_t = BenchConfig::default().warmup)]
+        warmup: usize,
+
+        /// Write the results as JSON to this file
+        #[arg(long)]
+        json: Option<PathBuf>,
+
+        /// Compare against a JSON report from an earlier run
+        #[arg(long)]
+        baseline: Option<PathBuf>,
+
+        /// Allowed median slowdown against the baseline before flagging a
+        /// regression, as a fraction
+        #[arg(long, default_value_t = 0.1)]
+        threshold: f64,
+    },
+    /// Step through the day 5 crate moves one at a time
+    Animate {
+        /// Crane model to move the crates with: 9000, 9001, capacity-N for
+        /// one that lifts at most N crates at a time, or alternating-N for
+        /// one that also flips every other lift
+        #[arg(long, default_value = "9000")]
+        crane: String,
+
+        /// Keep going past invalid moves, making as much of each as possible,
+        /// instead of stopping at the first one
+        #[arg(long)]
+        lenient: bool,
+
+        /// Start at this move instead of the starting stacks
+        #[arg(long, default_value_t = 0)]
+        step: usize,
+
+        /// Write every frame to this text file instead of stepping
+        /// interactively
+        #[arg(long)]
+        text: Option<PathBuf>,
+
+        /// Write every frame to this asciicast v2 file instead of stepping
+        /// interactively
+        #[arg(long)]
+        asciicast: Option<PathBuf>,
+
+        /// Seconds between frames in the asciicast
+        #[arg(long, default_value_t = 0.5)]
+        delay: f64,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Find the fewest day 5 moves that turn one stack drawing into another
+    Plan {
+        /// Drawing of the starting stacks; a full day 5 input works too
+        #[arg(long)]
+        from: PathBuf,
+
+        /// Drawing of the stacks to end up with
+        #[arg(long)]
+        to: PathBuf,
+
+        /// Crane model to plan for, as for `animate`
+        #[arg(long, default_value = "9000")]
+        crane: String,
+
+        /// Most search states to expand before giving up
+        #[arg(long, default_value_t = PlanConfig::default().max_states)]
+        max_states: usize,
+
+        /// Trade plan length for speed: plans are at most this many times
+        /// longer than the shortest
+        #[arg(long, default_value_t = PlanConfig::default().weight)]
+        weight: usize,
+    },
+    /// Time the String and compact crate stacks on a generated day 5 input
+    Scale {
+        /// Number of stacks
+        #[arg(long, default_value_t = SyntheticConfig::default().stacks)]
+        stacks: usize,
+
+        /// Crates on each stack to start with
+        #[arg(long, default_value_t = SyntheticConfig::default().height)]
+        height: usize,
+
+        /// Number of moves
+        #[arg(long, default_value_t = SyntheticConfig::default().moves)]
+        moves: usize,
+
+        /// Most crates a single move takes
+        #[arg(long, default_value_t = SyntheticConfig::default().max_batch)]
+        max_batch: usize,
+
+        /// Seed for the generator
+        #[arg(long, default_value_t = SyntheticConfig::default().seed)]
+        seed: u64,
+
+        /// Timed runs of each implementation
+        #[arg(long, default_value_t = 3)]
+        iterations: usize,
+    },
+    /// Stream a day 6 datastream and print every marker in it
+    Decode {
+        /// File to read; stdin when missing or `-`
+        path: Option<PathBuf>,
+
+        /// Different bytes in a start-of-packet marker
+        #[arg(long, default_value_t = SOP_SIZE)]
+        packet_size: usize,
+
+        /// Different bytes in a start-of-message marker
+        #[arg(long, default_value_t = SOM_SIZE)]
+        message_size: usize,
+    },
+    /// Explore the filesystem rebuilt from a day 7 transcript
+    Shell {
+        /// Day whose puzzle to explore; only day 7 has a shell
+        #[arg(value_parser = clap::value_parser!(u8).range(7..=7))]
+        day: u8,
+
+        /// Merge repeated listings and work around other inconsistencies,
+        /// printing them as warnings, instead of refusing the transcript
+        #[arg(long)]
+        merge: bool,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Pick the day 7 directories to delete that free enough space for the
+    /// update while removing as little as possible
+    Cleanup {
+        /// Keep this path, everything under it and every directory above
+        /// it; repeat for several
+        #[arg(long)]
+        protect: Vec<String>,
+
+        /// Delete at most this many directories
+        #[arg(long)]
+        max_deletions: Option<usize>,
+
+        /// Free space to end up with
+        #[arg(long, default_value_t = CleanupConfig::default().target)]
+        target: u64,
+
+        /// Most deletion sets to try before giving up
+        #[arg(long, default_value_t = CleanupConfig::default().max_steps)]
+        max_steps: usize,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Print the filesystem rebuilt from a day 7 transcript
+    Report {
+        /// How to print it; ncdu JSON can be opened with `ncdu -f`
+        #[arg(long, value_enum, default_value_t = ReportFormat::Tree)]
+        format: ReportFormat,
+
+        /// Only report on this directory; ncdu always exports everything
+        #[arg(long, default_value = "/")]
+        dir: String,
+
+        /// List du rows in post-order instead of by size
+        #[arg(long)]
+        post_order: bool,
+
+        #[command(flatten)]
+        input: InputArgs,
+    },
+    /// Write a day 7 terminal transcript that explores a real directory
+    Transcript {
+        /// Directory to explore
+        path: PathBuf,
+
+        /// List every directory at one depth before going deeper, instead
+        /// of depth first like the puzzle
+        #[arg(long)]
+        bfs: bool,
+
+        /// Add repeated `ls`s, and `cd ..`s and `cd /`s followed by the way
+        /// back, which don't change the tree. Replay it with `shell --merge`
+        #[arg(long)]
+        noise: bool,
+
+        /// Seed for where the noise goes
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// List every registered solution
+    List {
+        /// Manifest to read input variants from
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+}
+
+fn resolver(manifest: Option<&Path>) -> Result<InputResolver> {
+    match manifest {
+        Some(manifest) => InputResolver::from_manifest(manifest),
+        None => InputResolver::from_env(),
+    }
+}
+
+/// Every solution matching the day and name filters.
+fn filter<'a>(
+    days: &'a [RegisteredDay],
+    day: Option<u8>,
+    solution: Option<&str>,
+) -> Result<Vec<&'a RegisteredDay>> {
+    let selected: Vec<&RegisteredDay> = days
+        .iter()
+        .filter(|entry| day.is_none_or(|day| entry.day == day))
+        .filter(|entry| solution.is_none_or(|name| entry.name == name))
+        .collect();
+
+    if selected.is_empty() {
+        bail!("No solution registered for that selection");
+    }
+    Ok(selected)
+}
+
+/// Like [`filter`], but a bare day number only picks that day's first
+/// solution.
+fn select<'a>(
+    days: &'a [RegisteredDay],
+    day: Option<u8>,
+    solution: Option<&str>,
+) -> Result<Vec<&'a RegisteredDay>> {
+    let selected = filter(days, day, solution)?;
+    match (day, solution) {
+        (Some(_), None) => Ok(selected.into_iter().take(1).collect()),
+        _ => Ok(selected),
+    }
+}
+
+/// Reads the stack drawing at the top of a day 5 file.
+fn read_stacks(path: &Path) -> Result<ElfCrateStacks> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))?;
+    let drawing = content.split("\n\n").next().unwrap_or_default();
+    drawing
+        .trim_end()
+        .parse()
+        .with_context(|| format!("Could not parse the stacks in {}", path.display()))
+}
+
+/// Renders a parse error against the files the input came from, or adds
+/// context to any other error.
+fn parse_failure(mut err: Error, input: &LoadedInput, name: &str) -> Error {
+    let context = format!("Failed to parse input for {}", name);
+    match err.downcast_mut::<ParseError>() {
+        Some(parse_err) => {
+            parse_err.location.attribute(&input.files);
+            eprintln!("{}", parse_err.render());
+            Error::msg(context)
+        }
+        None => err.context(context),
+    }
+}
+
+/// Whether to colour drawings: only on a terminal, and not when `NO_COLOR`
+/// is set.
+fn use_color() -> bool {
+    io::stdout().is_terminal() && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Prints the answers for one solution and returns how many parts failed.
+fn run_day(
+    entry: &RegisteredDay,
+    input: &LoadedInput,
+    parts: &[Part],
+    visualize: bool,
+) -> Result<usize> {
+    println!("Day {} ({}) - {}", entry.day, entry.name, entry.title);
+
+    if visualize {
+        match entry.visualizer {
+            Some(visualizer) => match visualizer(&input.content, use_color()) {
+                Ok(drawing) => println!("{}\n", drawing),
+                Err(err) => eprintln!("warning: could not draw the input: {}", err),
+            },
+            None => println!("  (no visualization for this day)"),
+        }
+    }
+
+    let answers = entry
+        .solution
+        .run(&input.content, parts)
+        .map_err(|err| parse_failure(err, input, entry.name))?;
+
+    let mut failures = 0;
+    for (part, answer) in answers {
+        match answer {
+            Ok(answer) => println!("  Part {}: <{}>", part, answer),
+            Err(err) if err.is::<Unsolved>() => println!("  Part {}: ({})", part, err),
+            Err(err) => {
+                failures += 1;
+                println!("  Part {}: error: {:#}", part, err);
+            }
+        }
+    }
+    Ok(failures)
+}
+
+fn verify(days: &[&RegisteredDay], resolver: &InputResolver) -> Result<()> {
+    let answers = Answers::load(resolver)?;
+
+    let (mut passed, mut failed, mut missing, mut unsolved) = (0, 0, 0, 0);
+    for entry in days {
+        for report in verify_day(entry, resolver, &answers) {
+            match report.verdict {
+                Verdict::Pass => passed += 1,
+                Verdict::Fail { .. } | Verdict::Error(_) => failed += 1,
+                Verdict::Missing { .. } => missing += 1,
+                Verdict::Unsolved => unsolved += 1,
+            }
+            println!("{}", report);
+        }
+    }
+
+    println!(
+        "\n{} passed, {} failed, {} missing, {} unsolved",
+        passed, failed, missing, unsolved
+    );
+    if failed > 0 {
+        bail!("{} answer(s) did not match", failed);
+    }
+    Ok(())
+}
+
+fn bench(
+    days: &[&RegisteredDay],
+    resolver: &InputResolver,
+    variant: &str,
+    config: BenchConfig,
+) -> Result<BenchReport> {
+    let source = InputSource::Variant(String::from(variant));
+    let mut results = vec![];
+
+    for entry in days {
+        let input = match resolver.load(entry.day, &source) {
+            Ok(input) => input,
+            Err(err) => {
+                println!("Day {} ({}) - skipped: {:#}", entry.day, entry.name, err);
+                continue;
+            }
+        };
+        let timings = entry
+            .solution
+            .bench(&input, &config)
+            .with_context(|| format!("Failed to benchmark {}", entry.name))?;
+
+        println!("Day {} ({}) - {}", entry.day, entry.name, entry.title);
+        println!("  parse: {}", timings.parse);
+        for (label, stats) in [("part1", &timings.part1), ("part2", &timings.part2)] {
+            match stats {
+                Some(stats) => println!("  {}: {}", label, stats),
+                None => println!("  {}: (not solved yet)", label),
+            }
+        }
+
+        results.push(BenchResult {
+            name: String::from(entry.name),
+            day: entry.day,
+            variant: String::from(variant),
+            timings,
+        });
+    }
+
+    Ok(BenchReport { config, results })
+}
+
+/// Reads commands from stdin and redraws the stacks after each one until the
+/// input ends or the user quits.
+fn step_through(animation: &mut CrateAnimation) -> Result<()> {
+    let stdin = io::stdin();
+    let mut commands = stdin.lock().lines();
+    let color = use_color();
+
+    loop {
+        println!("{}\n", animation.render(color));
+        print!("[enter/n] next, [p] previous, [N] jump to move N, [q] quit: ");
+        io::stdout().flush()?;
+
+        let Some(command) = commands.next() else {
+            println!();
+            return Ok(());
+        };
+        match command?.trim() {
+            "" | "n" => {
+                animation.forward();
+            }
+            "p" | "b" => {
+                animation.back();
+            }
+            "q" => return Ok(()),
+            other => match other.parse::<usize>() {
+                Ok(n) => {
+                    animation.jump(n);
+                }
+                Err(_) => println!("Unknown command {:?}", other),
+            },
+        }
+    }
+}
+
+/// Runs the generated input through the String and compact stacks with both
+/// cranes and prints how long each took. The 9000 is also timed moving one
+/// crate at a time, the way it originally did.
+fn scale(config: &SyntheticConfig, iterations: usize) -> Result<()> {
+    let (compact, moves) = generate(config);
+    let stacks = ElfCrateStacks::from(&compact);
+    println!(
+        "{} stacks of {} crates, {} moves of up to {} crates",
+        config.stacks,
+        config.height,
+        moves.moves().len(),
+        config.max_batch
+    );
+
+    let bench = BenchConfig {
+        warmup: 0,
+        iterations,
+    };
+    let time_string = |crane: &dyn CrateMover| {
+        let mut tops = String::new();
+        let result = time_phase(&bench, || {
+            let mut stacks = stacks.clone();
+            for elf_move in moves.moves() {
+                crane.apply(&mut stacks, elf_move);
+            }
+            tops = stacks.get_tops_of_stacks().to_string();
+            Ok(())
+        })?;
+        Ok::<_, Error>((result, tops))
+    };
+    // Name, String crane, one-crate-at-a-time baseline and compact crane.
+    type ScaleCrane = (
+        &'static str,
+        &'static dyn CrateMover,
+        Option<&'static dyn CrateMover>,
+        CompactCrane,
+    );
+    let cranes: [ScaleCrane; 2] = [
+        (
+            "9000",
+            &CrateMover9000,
+            Some(&OneByOneCrateMover9000),
+            CompactCrane::CrateMover9000,
+        ),
+        ("9001", &CrateMover9001, None, CompactCrane::CrateMover9001),
+    ];
+    for (name, crane, one_by_one, compact_crane) in cranes {
+        let (string, string_tops) = time_string(crane)?;
+        let one_by_one = one_by_one.map(time_string).transpose()?;
+
+        let mut compact_tops = String::new();
+        let compact = time_phase(&bench, || {
+            let mut stacks = compact.clone();
+            stacks.apply_moves(&moves, compact_crane, MoveMode::Strict)?;
+            compact_tops = stacks.tops();
+            Ok(())
+        })?;
+
+        let agrees = one_by_one
+            .as_ref()
+            .is_none_or(|(_, tops)| *tops == string_tops);
+        if string_tops != compact_tops || !agrees {
+            bail!("The {} cranes disagree on the final stacks", name);
+        }
+        let speedup = |baseline: &PhaseStats| {
+            format!(
+                "{:.1}x faster ({} vs {} median)",
+                baseline.median_ns as f64 / compact.median_ns.max(1) as f64,
+                format_ns(compact.median_ns),
+                format_ns(baseline.median_ns)
+            )
+        };
+        println!("CrateMover {}", name);
+        if let Some((one_by_one, _)) = &one_by_one {
+            println!("  String, one crate at a time: {}", one_by_one);
+        }
+        println!("  String, bulk moves:          {}", string);
+        println!("  compact:                     {}", compact);
+        if let Some((one_by_one, _)) = &one_by_one {
+            println!("  {} than one crate at a time", speedup(one_by_one));
+        }
+        println!("  {} than String bulk moves", speedup(&string));
+    }
+    Ok(())
+}
+
+fn decode(reader: impl io::Read, packet_size: usize, message_size: usize) -> Result<()> {
+    let mut stdout = io::stdout().lock();
+    let reader = StripFinalNewline::new(reader);
+    for event in MarkerDecoder::with_sizes(reader, packet_size, message_size) {
+        match writeln!(stdout, "{}", event?) {
+            // Piped into something like `head` that has seen enough.
+            Err(err) if err.kind() == io::ErrorKind::BrokenPipe => break,
+            result => result?,
+        }
+    }
+    Ok(())
+}
+
+/// Reads shell commands from stdin until the input ends or the user exits.
+fn elf_shell(shell: &mut ElfShell) -> Result<()> {
+    let stdin = io::stdin();
+    let mut commands = stdin.lock().lines();
+    println!("{}", HELP);
+
+    loop {
+        print!("{}", shell.prompt());
+        io::stdout().flush()?;
+
+        let Some(command) = commands.next() else {
+            println!();
+            return Ok(());
+        };
+        match command?.trim() {
+            "exit" | "quit" => return Ok(()),
+            command => match shell.run(command) {
+                Ok(output) if output.is_empty() => {}
+                Ok(output) => println!("{}", output),
+                Err(err) => println!("error: {}", err),
+            },
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let days = registry();
+
+    match cli.command {
+        Command::Verify {
+            day,
+            solution,
+            manifest,
+        } => {
+            let resolver = resolver(manifest.as_deref())?;
+            verify(&filter(&days, day, solution.as_deref())?, &resolver)?;
+        }
+        Command::Bench {
+            day,
+            solution,
+            variant,
+            manifest,
+            iterations,
+            warmup,
+            json,
+            baseline,
+            threshold,
+        } => {
+            let resolver = resolver(manifest.as_deref())?;
+            let selected = filter(&days, day, solution.as_deref())?;
+            let config = BenchConfig { warmup, iterations };
+            let report = bench(&selected, &resolver, &variant, config)?;
+
+            if let Some(json) = json {
+                fs::write(&json, serde_json::to_string_pretty(&report)?)
+                    .with_context(|| format!("Could not write {}", json.display()))?;
+            }
+
+            if let Some(baseline) = baseline {
+                let baseline: BenchReport = serde_json::from_str(
+                    &fs::read_to_string(&baseline)
+                        .with_context(|| format!("Could not read {}", baseline.display()))?,
+                )?;
+                let regressions = find_regressions(&baseline, &report, threshold);
+                if !regressions.is_empty() {
+                    println!("\nRegressions against baseline:");
+                    for regression in regressions.iter() {
+                        println!("  {}", regression);
+                    }
+                    bail!("{} phase(s) regressed", regressions.len());
+                }
+                println!("\nNo regressions against baseline");
+            }
+        }
+        Command::Animate {
+            crane,
+            lenient,
+            step,
+            text,
+            asciicast,
+            delay,
+            input,
+        } => {
+            let content = input.resolver()?.load_with_files(5, &input.source())?;
+            let (stacks, moves) = Day5::parse(&content.content)
+                .map_err(|err| parse_failure(err, &content, "day5"))?;
+            let crane = crate_mover(&crane)?;
+            let mode = match lenient {
+                true => MoveMode::Lenient,
+                false => MoveMode::Strict,
+            };
+            let mut animation = CrateAnimation::new(&stacks, &moves, crane.as_ref(), mode)?;
+            for warning in animation.warnings() {
+                eprintln!("warning: {}", warning);
+            }
+            animation.jump(step);
+
+            if let Some(asciicast) = &asciicast {
+                fs::write(asciicast, animation.to_asciicast(delay)?)
+                    .with_context(|| format!("Could not write {}", asciicast.display()))?;
+            }
+            if let Some(text) = &text {
+                fs::write(text, animation.to_text())
+                    .with_context(|| format!("Could not write {}", text.display()))?;
+            }
+            if text.is_none() && asciicast.is_none() {
+                step_through(&mut animation)?;
+            }
+        }
+        Command::Plan {
+            from,
+            to,
+            crane,
+            max_states,
+            weight,
+        } => {
+            let config = PlanConfig { max_states, weight };
+            let plan = plan_moves(
+                &read_stacks(&from)?,
+                &read_stacks(&to)?,
+                crate_mover(&crane)?.as_ref(),
+                &config,
+            )?;
+            println!("{}", plan);
+        }
+        Command::Scale {
+            stacks,
+            height,
+            moves,
+            max_batch,
+            seed,
+            iterations,
+        } => {
+            let config = SyntheticConfig {
+                stacks,
+                height,
+                moves,
+                max_batch,
+                seed,
+            };
+            scale(&config, iterations)?;
+        }
+        Command::Decode {
+            path,
+            packet_size,
+            message_size,
+        } => match path {
+            Some(path) if path.as_os_str() != STDIN_PATH => {
+                let file = fs::File::open(&path)
+                    .with_context(|| format!("Could not open {}", path.display()))?;
+                decode(file, packet_size, message_size)?;
+            }
+            _ => decode(io::stdin().lock(), packet_size, message_size)?,
+        },
+        Command::Shell { day, merge, input } => {
+            let content = input.resolver()?.load_with_files(day, &input.source())?;
+            let commands = parse_transcript(&content.content)
+                .map_err(|err| parse_failure(err.into(), &content, "day7"))?;
+            let policy = match merge {
+                true => ReplayPolicy::Merge,
+                false => ReplayPolicy::Strict,
+            };
+            let (efs, warnings) = replay(&commands, policy)?;
+            for warning in warnings {
+                eprintln!("warning: {}", warning);
+            }
+            elf_shell(&mut ElfShell::new(efs))?;
+        }
+        Command::Cleanup {
+            protect,
+            max_deletions,
+            target,
+            max_steps,
+            input,
+        } => {
+            let content = input.resolver()?.load_with_files(7, &input.source())?;
+            let efs = process_efs(&content.content)
+                .map_err(|err| parse_failure(err, &content, "day7"))?;
+            let config = CleanupConfig {
+                target,
+                protected: protect
+                    .iter()
+                    .map(|path| ElfPath::from(path.as_str()))
+                    .collect(),
+                max_deletions,
+                max_steps,
+            };
+            println!("{}", plan_cleanup(&efs, &config)?.render(&efs));
+        }
+        Command::Report {
+            format,
+            dir,
+            post_order,
+            input,
+        } => {
+            let content = input.resolver()?.load_with_files(7, &input.source())?;
+            let efs = process_efs(&content.content)
+                .map_err(|err| parse_failure(err, &content, "day7"))?;
+            let id = match efs.resolve(efs.root(), &ElfPath::from(dir.as_str())) {
+                Some(id) if efs.is_dir(id) => id,
+                _ => bail!("{} is not a directory", dir),
+            };
+            let order = match post_order {
+                true => DuOrder::PostOrder,
+                false => DuOrder::Size,
+            };
+            match format {
+                ReportFormat::Tree => println!("{}", tree(&efs, id)),
+                ReportFormat::Du => println!("{}", du_table(&efs, id, order)),
+                ReportFormat::Ncdu => {
+                    let timestamp = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |elapsed| elapsed.as_secs());
+                    println!("{}", ncdu_export(&efs, timestamp));
+                }
+            }
+        }
+        Command::Transcript {
+            path,
+            bfs,
+            noise,
+            seed,
+        } => {
+            let config = TranscriptConfig {
+                order: match bfs {
+                    true => TraversalOrder::BreadthFirst,
+                    false => TraversalOrder::DepthFirst,
+                },
+                noise,
+                seed,
+            };
+            print!("{}", to_transcript(&read_dir_tree(&path)?, &config));
+        }
+        Command::List { manifest } => {
+            let resolver = resolver(manifest.as_deref())?;
+            for entry in days.iter() {
+                println!(
+                    "{:>2}  {:<12} {:<40} {}",
+                    entry.day,
+                    entry.name,
+                    entry.title,
+                    resolver.manifest().variants(entry.day).join(", ")
+                );
+            }
+        }
+        Command::Run {
+            day,
+            part,
+            all,
+            solution,
+            visualize,
+            input,
+        } => {
+            if all && !input.inputs.is_empty() {
+                bail!("--input can only be used when running a single day");
+            }
+            let resolver = input.resolver()?;
+            let source = input.source();
+
+            let parts = match part {
+                Some(1) => vec![Part::One],
+                Some(_) => vec![Part::Two],
+                None => vec![Part::One, Part::Two],
+            };
+
+            let mut failures = 0;
+            for entry in select(&days, day, solution.as_deref())? {
+                match resolver.load_with_files(entry.day, &source) {
+                    Ok(content) => match run_day(entry, &content, &parts, visualize) {
+                        Ok(failed) => failures += failed,
+                        // A day that can't parse its input fails every part,
+                        // but the other days still run.
+                        Err(err) if all => {
+                            println!("  error: {:#}", err);
+                            failures += parts.len();
+                        }
+                        Err(err) => return Err(err),
+                    },
+                    Err(err) if all => {
+                        println!("Day {} ({}) - skipped: {:#}", entry.day, entry.name, err)
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            if failures > 0 {
+                bail!("{} part(s) failed", failures);
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,41 +1,16 @@
-use std::collections::HashSet;
-use std::fs;
-
-use anyhow::{Ok, Result};
-use lazy_static::lazy_static;
-
-const INPUT_PATH: &str = "inputs/day6.txt";
-const SOP_SIZE: usize = 4;
-const SOM_SIZE: usize = 14;
-
-lazy_static! {
-    static ref STACKS_FILE: String =
-        fs::read_to_string(INPUT_PATH).expect("Day 6 - Inputs: Can't parse file");
-}
-
-fn count_to_first_marker(input: &str, marker_size: usize) -> u32 {
-    let mut count = marker_size as u32;
-    let mut input_seeker = input.chars();
-
-    for _ in 0..input.len() {
-        let char_set: HashSet<char> = input_seeker.clone().take(marker_size).collect();
-        if char_set.len() == marker_size {
-            break;
-        }
-        count = count + 1;
-        input_seeker.next();
-    }
-    count
-}
+use aoc_2022::{day6::Day6, input::load_puzzle_input, Result, Solution};
 
 fn main() -> Result<()> {
+    let input = Day6::parse(&load_puzzle_input(6)?)?;
+
     println!(
         "Part 1 - Chars to first start-of-packet: <{}>",
-        count_to_first_marker(&STACKS_FILE, SOP_SIZE)
+        Day6::part1(&input)?
     );
     println!(
         "Part 2 - Chars to first start-of-message: <{}>",
-        count_to_first_marker(&STACKS_FILE, SOM_SIZE)
+        Day6::part2(&input)?
     );
+
     Ok(())
 }
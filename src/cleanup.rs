@@ -0,0 +1,480 @@
+use std::collections::{BTreeMap, HashSet};
+
+use thiserror::Error;
+
+use crate::elf_fs::{ElfFileSystem, NodeId, UNUSED_SPACE_TARGET};
+use crate::elf_path::ElfPath;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum CleanupError {
+    #[error("protected path {0} doesn't exist")]
+    UnknownPath(ElfPath),
+    #[error("{needed} bytes need freeing but the allowed deletions free at most {available}")]
+    Unreachable { needed: u64, available: u64 },
+    #[error("gave up after exploring {0} deletion sets without finding a plan")]
+    SearchLimit(usize),
+}
+
+/// What the planner may delete.
+#[derive(Debug, Clone)]
+pub struct CleanupConfig {
+    /// Free space to end up with.
+    pub target: u64,
+    /// Paths that must survive with everything under them, so neither they,
+    /// anything inside them nor any directory holding them can be deleted.
+    pub protected: Vec<ElfPath>,
+    /// Most directories to delete.
+    pub max_deletions: Option<usize>,
+    /// Most deletion sets to try. A plan found by then is returned even if
+    /// a better one might exist.
+    pub max_steps: usize,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            target: UNUSED_SPACE_TARGET,
+            protected: vec![],
+            max_deletions: None,
+            max_steps: 10_000_000,
+        }
+    }
+}
+
+/// Directories to delete, none inside another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CleanupPlan {
+    pub dirs: Vec<NodeId>,
+    pub freed: u64,
+    pub free_before: u64,
+    pub free_after: u64,
+    /// Whether no plan that frees enough removes fewer bytes. False when the
+    /// search ran out of steps first.
+    pub optimal: bool,
+    /// How many deletion sets the search tried.
+    pub steps: usize,
+}
+
+impl CleanupPlan {
+    /// One deleted directory per line, then the free space before and after.
+    pub fn render(&self, efs: &ElfFileSystem) -> String {
+        let mut lines: Vec<String> = self
+            .dirs
+            .iter()
+            .map(|&dir| format!("rm {:<40} {:>10}", efs.path(dir).to_string(), efs.size(dir)))
+            .collect();
+        lines.push(format!(
+            "{} directories, {} bytes freed",
+            self.dirs.len(),
+            self.freed
+        ));
+        lines.push(format!(
+            "free space: {} before, {} after",
+            self.free_before, self.free_after
+        ));
+        if !self.optimal {
+            lines.push(String::from(
+                "(the search ran out of steps, a smaller plan may exist)",
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Depth-first search over the deletable directories in pre-order, so a
+/// directory's subtree is the run of candidates right after it.
+struct Search<'a> {
+    sizes: Vec<u64>,
+    /// Index of the first candidate after each candidate's subtree.
+    skip: Vec<usize>,
+    /// The most a search from each index can still free.
+    reach: Vec<u64>,
+    /// The smallest candidate from each index on, the least one more
+    /// deletion adds.
+    smallest: Vec<u64>,
+    needed: u64,
+    /// No plan frees less than this, so finding one that frees exactly this
+    /// much ends the search.
+    floor: u64,
+    max_deletions: usize,
+    max_steps: usize,
+    steps: usize,
+    chosen: Vec<usize>,
+    best: Option<(u64, Vec<usize>)>,
+    candidates: &'a [NodeId],
+}
+
+/// A point in the search: deciding about a candidate, or coming back from
+/// deleting one to try keeping it instead.
+enum Step {
+    Visit { idx: usize, freed: u64 },
+    Keep { idx: usize, freed: u64 },
+}
+
+impl Search<'_> {
+    /// Whether a plan freeing at least `freed` bytes with `deletions`
+    /// directories can't beat the best one so far.
+    fn beaten(&self, freed: u64, deletions: usize) -> bool {
+        self.best
+            .as_ref()
+            .is_some_and(|(best, dirs)| (freed, deletions) >= (*best, dirs.len()))
+    }
+
+    fn done(&self) -> bool {
+        self.steps > self.max_steps
+            || self
+                .best
+                .as_ref()
+                .is_some_and(|(best, _)| *best == self.floor)
+    }
+
+    /// Tries deleting each candidate before keeping it, with an explicit
+    /// stack so deep trees can't overflow the call stack.
+    fn run(&mut self) {
+        let mut stack = vec![Step::Visit { idx: 0, freed: 0 }];
+        while let Some(step) = stack.pop() {
+            let (idx, freed) = match step {
+                Step::Visit { idx, freed } => (idx, freed),
+                Step::Keep { idx, freed } => {
+                    self.chosen.pop();
+                    stack.push(Step::Visit {
+                        idx: idx + 1,
+                        freed,
+                    });
+                    continue;
+                }
+            };
+            self.steps += 1;
+            if self.done() {
+                return;
+            }
+            if freed >= self.needed {
+                let better = match &self.best {
+                    Some((best, dirs)) => (freed, self.chosen.len()) < (*best, dirs.len()),
+                    None => true,
+                };
+                if better {
+                    self.best = Some((freed, self.chosen.clone()));
+                }
+                continue;
+            }
+            if idx == self.candidates.len()
+                || self.chosen.len() == self.max_deletions
+                || freed + self.reach[idx] < self.needed
+            {
+                continue;
+            }
+            // Not enough is freed yet, so any plan from here deletes at least
+            // one more candidate and frees at least `needed`.
+            let least = (freed + self.smallest[idx]).max(self.needed);
+            if self.beaten(least, self.chosen.len() + 1) {
+                continue;
+            }
+
+            let deleted = freed + self.sizes[idx];
+            if self.beaten(deleted, self.chosen.len() + 1) {
+                stack.push(Step::Visit {
+                    idx: idx + 1,
+                    freed,
+                });
+                continue;
+            }
+            self.chosen.push(idx);
+            stack.push(Step::Keep { idx, freed });
+            stack.push(Step::Visit {
+                idx: self.skip[idx],
+                freed: deleted,
+            });
+        }
+    }
+}
+
+/// Most 64-bit words [`smallest_total`] may hold at once, 64 MiB.
+const MAX_BITSET_WORDS: u64 = 1 << 23;
+
+/// `dst |= src << shift`, dropping bits past the end.
+fn shift_or(dst: &mut [u64], src: &[u64], shift: u64) {
+    let (words, bits) = ((shift / 64) as usize, (shift % 64) as u32);
+    for idx in (words..dst.len()).rev() {
+        let from = idx - words;
+        let mut word = src[from] << bits;
+        if bits > 0 && from > 0 {
+            word |= src[from - 1] >> (64 - bits);
+        }
+        dst[idx] |= word;
+    }
+}
+
+/// The smallest total of at least `needed` bytes that some set of
+/// candidates frees, ignoring any limit on how many are deleted, found by
+/// tracking every reachable total up to `limit` in a bitset. The search
+/// then knows when it can stop.
+///
+/// One bitset is pending for each candidate whose subtree is still being
+/// walked, plus one, so up to `nesting + 1` of `limit / 64 + 1` words each
+/// are alive at once.
+fn smallest_total(sizes: &[u64], skip: &[usize], needed: u64, limit: u64) -> Option<u64> {
+    let words = (limit / 64 + 1) as usize;
+    // The totals reachable when the next decision is about each candidate;
+    // only the ones some earlier candidate can jump to are held at once.
+    let mut pending: BTreeMap<usize, Vec<u64>> = BTreeMap::new();
+    let mut start = vec![0; words];
+    start[0] = 1;
+    pending.insert(0, start);
+
+    while let Some((idx, totals)) = pending.pop_first() {
+        if idx == sizes.len() {
+            return (needed..=limit)
+                .find(|&total| totals[(total / 64) as usize] >> (total % 64) & 1 == 1);
+        }
+        if sizes[idx] <= limit {
+            let taken = pending.entry(skip[idx]).or_insert_with(|| vec![0; words]);
+            shift_or(taken, &totals, sizes[idx]);
+        }
+        let skipped = pending.entry(idx + 1).or_insert_with(|| vec![0; words]);
+        skipped
+            .iter_mut()
+            .zip(&totals)
+            .for_each(|(word, total)| *word |= total);
+    }
+    None
+}
+
+/// Finds the directories to delete that free enough space to reach
+/// `config.target` while removing as few bytes as possible, preferring
+/// fewer deletions among equally small plans.
+pub fn plan_cleanup(
+    efs: &ElfFileSystem,
+    config: &CleanupConfig,
+) -> Result<CleanupPlan, CleanupError> {
+    let protected = config
+        .protected
+        .iter()
+        .map(|path| {
+            efs.resolve(efs.root(), path)
+                .ok_or_else(|| CleanupError::UnknownPath(path.clone()))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The root, protected paths and the directories above them can't go,
+    // and neither can anything under a protected path.
+    let mut kept = HashSet::from([efs.root()]);
+    for &path in &protected {
+        let mut current = Some(path);
+        while let Some(id) = current.filter(|&id| kept.insert(id)) {
+            current = efs.node(id).parent;
+        }
+    }
+
+    // Candidates in pre-order, each with the index just past its subtree.
+    let mut candidates = vec![];
+    let mut skip = vec![];
+    // Most candidates nested inside each other, and how many are right now.
+    let (mut nesting, mut open) = (0, 0);
+    // Each directory with whether it is under a protected path, or the
+    // candidate it closes.
+    let mut stack = vec![(efs.root(), false, None)];
+    while let Some((dir, inside, exiting)) = stack.pop() {
+        if let Some(idx) = exiting {
+            skip[idx] = candidates.len();
+            open -= 1;
+            continue;
+        }
+        if !inside && !kept.contains(&dir) {
+            open += 1;
+            nesting = nesting.max(open);
+            stack.push((dir, inside, Some(candidates.len())));
+            candidates.push(dir);
+            skip.push(0);
+        }
+        stack.extend(
+            efs.children(dir)
+                .iter()
+                .rev()
+                .filter(|&&child| efs.is_dir(child))
+                .map(|&child| (child, inside || protected.contains(&dir), None)),
+        );
+    }
+
+    let sizes: Vec<u64> = candidates.iter().map(|&dir| efs.size(dir)).collect();
+    let mut reach = vec![0; candidates.len() + 1];
+    for idx in (0..candidates.len()).rev() {
+        reach[idx] = (sizes[idx] + reach[skip[idx]]).max(reach[idx + 1]);
+    }
+    let mut smallest = vec![u64::MAX; candidates.len() + 1];
+    for idx in (0..candidates.len()).rev() {
+        smallest[idx] = sizes[idx].min(smallest[idx + 1]);
+    }
+
+    let free_before = efs.free_space();
+    let needed = config.target.saturating_sub(free_before);
+    let single = sizes.iter().copied().filter(|&size| size >= needed).min();
+    let floor = match single {
+        Some(limit) if (limit / 64 + 1) * (nesting as u64 + 1) <= MAX_BITSET_WORDS => {
+            smallest_total(&sizes, &skip, needed, limit).unwrap_or(needed)
+        }
+        _ => needed,
+    };
+    let mut search = Search {
+        needed,
+        floor,
+        max_deletions: config.max_deletions.unwrap_or(usize::MAX),
+        max_steps: config.max_steps,
+        steps: 0,
+        chosen: vec![],
+        best: None,
+        candidates: &candidates,
+        sizes,
+        skip,
+        reach,
+        smallest,
+    };
+    search.run();
+
+    let optimal = search.steps <= search.max_steps;
+    let Some((freed, chosen)) = search.best else {
+        return Err(match optimal {
+            true => CleanupError::Unreachable {
+                needed,
+                available: search.reach[0],
+            },
+            false => CleanupError::SearchLimit(config.max_steps),
+        });
+    };
+    Ok(CleanupPlan {
+        dirs: chosen.into_iter().map(|idx| candidates[idx]).collect(),
+        freed,
+        free_before,
+        free_after: free_before + freed,
+        optimal,
+        steps: search.steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_cleanup, CleanupConfig, CleanupError};
+    use crate::elf_fs::{process_efs, ElfEntryKind, ElfFileSystem};
+    use crate::elf_path::ElfPath;
+
+    /// 50M used, so 10M need freeing. `x` and `y` together free less than
+    /// `z` alone.
+    fn disk() -> ElfFileSystem {
+        let mut efs = ElfFileSystem::new();
+        let root = efs.root();
+        efs.add(root, "big", ElfEntryKind::File { size: 27_000_000 });
+        for (name, size) in [("x", 6_000_000), ("y", 5_000_000), ("z", 12_000_000)] {
+            let dir = efs.add(root, name, ElfEntryKind::Dir);
+            let inner = efs.add(dir, "inner", ElfEntryKind::Dir);
+            efs.add(inner, "data", ElfEntryKind::File { size });
+        }
+        efs
+    }
+
+    fn paths(efs: &ElfFileSystem, config: &CleanupConfig) -> Vec<String> {
+        let plan = plan_cleanup(efs, config).unwrap();
+        plan.dirs
+            .iter()
+            .map(|&dir| efs.path(dir).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn it_matches_part_2_on_the_example() {
+        let efs = process_efs(include_str!("../inputs/examples/day7.txt")).unwrap();
+        let plan = plan_cleanup(&efs, &CleanupConfig::default()).unwrap();
+
+        assert_eq!(plan.freed, 24933642);
+        assert_eq!(plan.free_after, 21618835 + 24933642);
+    }
+
+    #[test]
+    fn it_combines_directories_to_remove_less() {
+        let efs = disk();
+
+        assert_eq!(paths(&efs, &CleanupConfig::default()), vec!["/x", "/y"]);
+        let config = CleanupConfig {
+            max_deletions: Some(1),
+            ..CleanupConfig::default()
+        };
+        assert_eq!(paths(&efs, &config), vec!["/z"]);
+
+        let config = CleanupConfig {
+            max_steps: 1,
+            ..CleanupConfig::default()
+        };
+        assert_eq!(
+            plan_cleanup(&efs, &config).unwrap_err(),
+            CleanupError::SearchLimit(1)
+        );
+    }
+
+    #[test]
+    fn it_prunes_plans_that_cant_beat_the_best() {
+        // 51M used, so 11M need freeing, but every size is even and no
+        // directory frees enough alone, so the floor is no help.
+        let mut efs = ElfFileSystem::new();
+        let root = efs.root();
+        efs.add(root, "big", ElfEntryKind::File { size: 23_000_000 });
+        for (idx, size) in [8, 6, 4, 4, 2, 2, 2].into_iter().enumerate() {
+            let dir = efs.add(root, &format!("d{idx}"), ElfEntryKind::Dir);
+            efs.add(
+                dir,
+                "data",
+                ElfEntryKind::File {
+                    size: size * 1_000_000,
+                },
+            );
+        }
+
+        let plan = plan_cleanup(&efs, &CleanupConfig::default()).unwrap();
+        assert_eq!((plan.freed, plan.dirs.len()), (12_000_000, 2));
+        assert!(plan.optimal);
+        // Without bounding by the best plan so far the search tries 59 sets.
+        assert_eq!(plan.steps, 30);
+    }
+
+    #[test]
+    fn it_never_deletes_the_root() {
+        let mut efs = ElfFileSystem::new();
+        let root = efs.root();
+        efs.add(root, "big", ElfEntryKind::File { size: 60_000_000 });
+
+        assert_eq!(
+            plan_cleanup(&efs, &CleanupConfig::default()).unwrap_err(),
+            CleanupError::Unreachable {
+                needed: 20_000_000,
+                available: 0
+            }
+        );
+    }
+
+    #[test]
+    fn it_keeps_protected_paths() {
+        let efs = disk();
+        let config = CleanupConfig {
+            protected: vec![ElfPath::from("/y/inner/data")],
+            ..CleanupConfig::default()
+        };
+        assert_eq!(paths(&efs, &config), vec!["/z"]);
+
+        // `/x/inner` and `/y/inner` would free just as much as `/x` and `/y`.
+        let config = CleanupConfig {
+            protected: vec![ElfPath::from("/x"), ElfPath::from("/y")],
+            ..CleanupConfig::default()
+        };
+        assert_eq!(paths(&efs, &config), vec!["/z"]);
+
+        let config = CleanupConfig {
+            protected: vec![ElfPath::from("/y"), ElfPath::from("/z/inner")],
+            ..CleanupConfig::default()
+        };
+        assert_eq!(
+            plan_cleanup(&efs, &config).unwrap_err(),
+            CleanupError::Unreachable {
+                needed: 10_000_000,
+                available: 6_000_000
+            }
+        );
+    }
+}
@@ -0,0 +1,255 @@
+use thiserror::Error;
+
+use crate::day5::{
+    validate_move, CrateMoveError, ElfCrateMove, ElfCrateMoves, ElfCrateStacks, MoveMode,
+};
+
+#[derive(Debug, Error)]
+#[error("crate {0:?} is not a single byte, compact stacks need one-byte labels")]
+pub struct WideLabelError(String);
+
+/// The two puzzle cranes, for [`CompactCrateStacks`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactCrane {
+    CrateMover9000,
+    CrateMover9001,
+}
+
+/// [`ElfCrateStacks`] for big inputs: one byte per crate and every move is a
+/// single bulk copy, whichever crane makes it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompactCrateStacks {
+    stacks: Vec<Vec<u8>>,
+}
+
+impl CompactCrateStacks {
+    /// Every stack, bottom crate first.
+    pub fn stacks(&self) -> &[Vec<u8>] {
+        &self.stacks
+    }
+
+    /// The top crate of every stack, with a space for empty stacks.
+    pub fn tops(&self) -> String {
+        self.stacks
+            .iter()
+            .map(|stack| stack.last().map_or(' ', |&label| label as char))
+            .collect()
+    }
+
+    pub fn check_move(&self, index: usize, elf_move: &ElfCrateMove) -> Result<(), CrateMoveError> {
+        validate_move(index, elf_move, self.stacks.len(), |stack| {
+            self.stacks[stack].len()
+        })
+    }
+
+    /// The source and destination stacks, borrowed together.
+    fn stack_pair(&mut self, from: usize, to: usize) -> (&mut Vec<u8>, &mut Vec<u8>) {
+        if from < to {
+            let (low, high) = self.stacks.split_at_mut(to);
+            (&mut low[from], &mut high[0])
+        } else {
+            let (low, high) = self.stacks.split_at_mut(from);
+            (&mut high[0], &mut low[to])
+        }
+    }
+
+    /// Makes as much of `elf_move` as the stacks allow, like the
+    /// [`crate::day5::CrateMover`] cranes do.
+    pub fn crane_move(&mut self, elf_move: &ElfCrateMove, crane: CompactCrane) {
+        let (from, to) = (elf_move.from_stack(), elf_move.to_stack());
+        if from == to || from == 0 || to == 0 || from.max(to) > self.stacks.len() {
+            return;
+        }
+
+        let (from_stack, to_stack) = self.stack_pair(from - 1, to - 1);
+        let split_point = from_stack.len().saturating_sub(elf_move.move_num());
+        match crane {
+            CompactCrane::CrateMover9000 => to_stack.extend(from_stack[split_point..].iter().rev()),
+            CompactCrane::CrateMover9001 => to_stack.extend_from_slice(&from_stack[split_point..]),
+        }
+        from_stack.truncate(split_point);
+    }
+
+    /// Runs every move through `crane`, with the same validation as
+    /// [`ElfCrateStacks::apply_moves`] but no undo journal.
+    pub fn apply_moves(
+        &mut self,
+        elf_moves: &ElfCrateMoves,
+        crane: CompactCrane,
+        mode: MoveMode,
+    ) -> Result<Vec<CrateMoveError>, CrateMoveError> {
+        let mut warnings = vec![];
+        for (idx, elf_move) in elf_moves.moves().iter().enumerate() {
+            if let Err(err) = self.check_move(idx + 1, elf_move) {
+                match mode {
+                    MoveMode::Strict => return Err(err),
+                    MoveMode::Lenient => warnings.push(err),
+                }
+            }
+            self.crane_move(elf_move, crane);
+        }
+        Ok(warnings)
+    }
+}
+
+impl From<Vec<Vec<u8>>> for CompactCrateStacks {
+    fn from(stacks: Vec<Vec<u8>>) -> Self {
+        Self { stacks }
+    }
+}
+
+impl TryFrom<&ElfCrateStacks> for CompactCrateStacks {
+    type Error = WideLabelError;
+
+    fn try_from(stacks: &ElfCrateStacks) -> Result<Self, Self::Error> {
+        let stacks = stacks
+            .stacks()
+            .iter()
+            .map(|stack| {
+                stack
+                    .iter()
+                    .map(|label| match label.as_bytes() {
+                        [byte] => Ok(*byte),
+                        _ => Err(WideLabelError(label.clone())),
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { stacks })
+    }
+}
+
+impl From<&CompactCrateStacks> for ElfCrateStacks {
+    fn from(stacks: &CompactCrateStacks) -> Self {
+        ElfCrateStacks::from(
+            stacks
+                .stacks
+                .iter()
+                .map(|stack| {
+                    stack
+                        .iter()
+                        .map(|&label| String::from(label as char))
+                        .collect()
+                })
+                .collect::<Vec<Vec<String>>>(),
+        )
+    }
+}
+
+/// Shape of a generated input.
+#[derive(Debug, Clone, Copy)]
+pub struct SyntheticConfig {
+    pub stacks: usize,
+    /// Crates on each stack to start with.
+    pub height: usize,
+    pub moves: usize,
+    /// Most crates a single move takes.
+    pub max_batch: usize,
+    pub seed: u64,
+}
+
+impl Default for SyntheticConfig {
+    fn default() -> Self {
+        Self {
+            stacks: 1_000,
+            height: 100,
+            moves: 1_000_000,
+            max_batch: 50,
+            seed: 2022,
+        }
+    }
+}
+
+/// SplitMix64, so generated inputs are the same on every machine.
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub(crate) fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+}
+
+/// Generates stacks of random `A`-`Z` crates and a move list that is valid
+/// for them, whichever crane runs it.
+pub fn generate(config: &SyntheticConfig) -> (CompactCrateStacks, ElfCrateMoves) {
+    let mut rng = SplitMix64(config.seed);
+    let stacks: Vec<Vec<u8>> = (0..config.stacks)
+        .map(|_| {
+            (0..config.height)
+                .map(|_| b'A' + rng.below(26) as u8)
+                .collect()
+        })
+        .collect();
+
+    // Moves only depend on how many crates each stack holds.
+    let mut heights = vec![config.height; config.stacks];
+    let mut moves = Vec::with_capacity(config.moves);
+    if config.stacks >= 2 && config.height > 0 {
+        while moves.len() < config.moves {
+            let from = rng.below(config.stacks);
+            if heights[from] == 0 {
+                continue;
+            }
+            let to = (from + 1 + rng.below(config.stacks - 1)) % config.stacks;
+            let move_num = 1 + rng.below(heights[from].min(config.max_batch.max(1)));
+
+            heights[from] -= move_num;
+            heights[to] += move_num;
+            moves.push(ElfCrateMove::new(move_num, from + 1, to + 1));
+        }
+    }
+
+    (CompactCrateStacks::from(stacks), ElfCrateMoves::from(moves))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{generate, CompactCrane, CompactCrateStacks, SyntheticConfig};
+    use crate::day5::{CrateMover, CrateMover9000, CrateMover9001, ElfCrateStacks, MoveMode};
+
+    #[test]
+    fn it_matches_the_string_stacks() {
+        let config = SyntheticConfig {
+            stacks: 9,
+            height: 8,
+            moves: 2_000,
+            max_batch: 6,
+            seed: 7,
+        };
+        let (compact, moves) = generate(&config);
+        let stacks = ElfCrateStacks::from(&compact);
+
+        for (compact_crane, crane) in [
+            (
+                CompactCrane::CrateMover9000,
+                &CrateMover9000 as &dyn CrateMover,
+            ),
+            (CompactCrane::CrateMover9001, &CrateMover9001),
+        ] {
+            let mut compact = compact.clone();
+            compact
+                .apply_moves(&moves, compact_crane, MoveMode::Strict)
+                .unwrap();
+            let mut stacks = stacks.clone();
+            stacks.apply_moves(&moves, crane, MoveMode::Strict).unwrap();
+
+            assert_eq!(compact.tops(), stacks.get_tops_of_stacks().to_string());
+            assert_eq!(CompactCrateStacks::try_from(&stacks).unwrap(), compact);
+        }
+    }
+
+    #[test]
+    fn it_rejects_wide_labels() {
+        let stacks = ElfCrateStacks::from(vec![vec![String::from("AB")]]);
+
+        assert!(CompactCrateStacks::try_from(&stacks).is_err());
+    }
+}
@@ -0,0 +1,233 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::diagnostic::ParseError;
+pub use crate::elf_fs::BIG_DIRECTORY_SIZE;
+use crate::elf_fs::{ElfTerminalError, TOTAL_DISK_SIZE, UNUSED_SPACE_TARGET};
+use crate::Solution;
+
+pub struct CursedDay7;
+
+pub trait ElfSized {
+    fn full_size(&self) -> u64;
+}
+
+#[derive(Debug)]
+struct ElfDirectorys(HashMap<String, Rc<RefCell<ElfDirectory>>>);
+
+#[derive(Debug)]
+struct ElfFiles(HashMap<String, ElfFile>);
+
+#[derive(Debug)]
+struct ElfFile {
+    size: u64,
+}
+
+#[derive(Debug)]
+enum ElfParentDirectory {
+    Root,
+    NonRoot(Rc<RefCell<ElfDirectory>>),
+}
+
+#[derive(Debug)]
+pub struct ElfDirectory {
+    parent: ElfParentDirectory,
+    directories: ElfDirectorys,
+    files: ElfFiles,
+}
+
+enum ElfTerminalLine {
+    CdCommand { dir: String },
+    LsCommand,
+    FileListing { size: u64, file: String },
+    DirectoryListing { dir: String },
+}
+
+impl ElfSized for ElfDirectory {
+    fn full_size(&self) -> u64 {
+        self.directories
+            .0
+            .iter()
+            .map(|c| c.1.try_borrow())
+            .filter_map(|f| f.ok())
+            .map(|g| g.full_size())
+            .sum::<u64>()
+            + self.files.0.iter().map(|c| c.1.full_size()).sum::<u64>()
+    }
+}
+
+impl ElfSized for ElfFile {
+    fn full_size(&self) -> u64 {
+        self.size
+    }
+}
+
+impl ElfDirectory {
+    fn special_size(&self) -> u64 {
+        let full = self.full_size();
+        let this_special = if full < BIG_DIRECTORY_SIZE { full } else { 0 };
+
+        self.directories
+            .0
+            .iter()
+            .map(|c| c.1.try_borrow())
+            .filter_map(|f| f.ok())
+            .map(|g| g.special_size())
+            .sum::<u64>()
+            + this_special
+    }
+
+    /// Pushes the full size of this directory and of every one under it.
+    fn dir_sizes(&self, sizes: &mut Vec<u64>) {
+        sizes.push(self.full_size());
+        for dir in self.directories.0.values() {
+            if let Ok(dir) = dir.try_borrow() {
+                dir.dir_sizes(sizes);
+            }
+        }
+    }
+
+    pub fn get_size_sort(&self) -> Vec<(&String, &Rc<RefCell<ElfDirectory>>)> {
+        let mut dir_vec: Vec<_> = self.directories.0.iter().collect();
+
+        dir_vec.sort_by(|a, b| {
+            let a_size = a.1.try_borrow().unwrap().full_size();
+            let b_size = b.1.try_borrow().unwrap().full_size();
+
+            a_size.cmp(&b_size)
+        });
+
+        dir_vec
+    }
+}
+
+fn process_terminal_line(
+    root: &Rc<RefCell<ElfDirectory>>,
+    dir_rc_cell: Rc<RefCell<ElfDirectory>>,
+    command: &ElfTerminalLine,
+) -> Result<Rc<RefCell<ElfDirectory>>> {
+    match command {
+        ElfTerminalLine::DirectoryListing { dir } => {
+            dir_rc_cell.try_borrow_mut()?.directories.0.insert(
+                dir.clone(),
+                Rc::new(RefCell::new(ElfDirectory {
+                    parent: ElfParentDirectory::NonRoot(Rc::clone(&dir_rc_cell)),
+                    files: ElfFiles(HashMap::new()),
+                    directories: ElfDirectorys(HashMap::new()),
+                })),
+            );
+            Ok(Rc::clone(&dir_rc_cell))
+        }
+        ElfTerminalLine::FileListing { size, file } => {
+            dir_rc_cell
+                .try_borrow_mut()?
+                .files
+                .0
+                .insert(file.clone(), ElfFile { size: *size });
+            Ok(Rc::clone(&dir_rc_cell))
+        }
+        ElfTerminalLine::CdCommand { dir } => match dir.as_str() {
+            "/" => Ok(Rc::clone(root)),
+            ".." => {
+                let parent = &dir_rc_cell.try_borrow()?.parent;
+
+                match parent {
+                    ElfParentDirectory::Root => bail!("Can not cd past root!"),
+                    ElfParentDirectory::NonRoot(parent_dir) => Ok(Rc::clone(parent_dir)),
+                }
+            }
+            _ => {
+                if let Some(child_dir_cell) = dir_rc_cell.borrow_mut().directories.0.get_mut(dir) {
+                    Ok(Rc::clone(child_dir_cell))
+                } else {
+                    bail!("Processing error")
+                }
+            }
+        },
+        ElfTerminalLine::LsCommand => Ok(Rc::clone(&dir_rc_cell)),
+    }
+}
+
+fn line_mapper(input: &str, line: &str) -> Result<ElfTerminalLine, ParseError> {
+    let malformed = || ParseError::new(input, line, ElfTerminalError::MalformedListing);
+    let (first, rest) = line.split_once(' ').ok_or_else(malformed)?;
+
+    match (first, rest) {
+        ("$", "ls") => Ok(ElfTerminalLine::LsCommand),
+        ("$", command) => match command.strip_prefix("cd ") {
+            Some(dir) => Ok(ElfTerminalLine::CdCommand {
+                dir: String::from(dir),
+            }),
+            None => Err(ParseError::new(
+                input,
+                command,
+                ElfTerminalError::UnknownCommand(String::from(command)),
+            )),
+        },
+        ("dir", dir) => Ok(ElfTerminalLine::DirectoryListing {
+            dir: String::from(dir),
+        }),
+        (size, file) => match size.parse::<u64>() {
+            Ok(size) => Ok(ElfTerminalLine::FileListing {
+                size,
+                file: String::from(file),
+            }),
+            Err(error) => Err(ParseError::new(input, size, ElfTerminalError::from(error))),
+        },
+    }
+}
+
+pub fn construct_file_system(terminal_output: &str) -> Result<Rc<RefCell<ElfDirectory>>> {
+    let command_iter = terminal_output
+        .lines()
+        .map(|line| line_mapper(terminal_output, line));
+
+    let root_dir_rc_cell = Rc::new(RefCell::new(ElfDirectory {
+        parent: ElfParentDirectory::Root,
+        directories: ElfDirectorys(HashMap::new()),
+        files: ElfFiles(HashMap::new()),
+    }));
+
+    {
+        let mut cursor_dir_root_rc = Rc::clone(&root_dir_rc_cell);
+
+        for line_res in command_iter {
+            cursor_dir_root_rc =
+                process_terminal_line(&root_dir_rc_cell, cursor_dir_root_rc, &line_res?)?;
+        }
+    }
+
+    Ok(root_dir_rc_cell)
+}
+
+impl Solution for CursedDay7 {
+    type Input = Rc<RefCell<ElfDirectory>>;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        construct_file_system(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64> {
+        let size = input.try_borrow()?.special_size();
+        Ok(size)
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64> {
+        let root = input.try_borrow()?;
+        let free_space = TOTAL_DISK_SIZE.saturating_sub(root.full_size());
+        let target = UNUSED_SPACE_TARGET.saturating_sub(free_space);
+
+        let mut sizes = vec![];
+        root.dir_sizes(&mut sizes);
+        sizes
+            .into_iter()
+            .filter(|&size| size > target)
+            .min()
+            .ok_or_else(|| anyhow!("no directory is big enough to free up the space"))
+    }
+}
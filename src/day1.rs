@@ -0,0 +1,188 @@
+use std::iter;
+use std::num::{IntErrorKind, ParseIntError};
+
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+pub struct Day1;
+
+/// One line of the calorie list: a snack, or `None` for the blank line
+/// that closes off an elf.
+pub type CalorieLine = Option<i32>;
+
+#[derive(Debug, Error)]
+pub enum CalorieError {
+    #[error("invalid calorie count: {0}")]
+    InvalidCalories(#[from] ParseIntError),
+}
+
+pub struct CalorieCounter {
+    largest_calories: i32,
+    calorie_stack: Vec<i32>,
+}
+
+impl CalorieCounter {
+    pub fn new() -> Self {
+        CalorieCounter {
+            largest_calories: 0,
+            calorie_stack: vec![],
+        }
+    }
+
+    fn push_to_stack(&mut self, val: i32) {
+        self.calorie_stack.push(val);
+    }
+
+    fn sum_and_compare_stack(&mut self) {
+        let count: i32 = self.calorie_stack.iter().sum();
+        if count > self.largest_calories {
+            self.largest_calories = count;
+        }
+        self.calorie_stack = vec![];
+    }
+}
+
+impl Default for CalorieCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Elf {
+    calories: i32,
+}
+
+pub struct ElfCalorieCounter {
+    top_elfs: Vec<Elf>,
+    elf_calorie_stack: Vec<i32>,
+}
+
+impl ElfCalorieCounter {
+    pub fn new() -> Self {
+        ElfCalorieCounter {
+            top_elfs: vec![],
+            elf_calorie_stack: vec![],
+        }
+    }
+
+    fn push_to_calorie_stack(&mut self, val: i32) {
+        self.elf_calorie_stack.push(val);
+    }
+
+    fn compare_and_add_elf(&mut self, new_elf: Elf) {
+        if self.top_elfs.is_empty() {
+            self.top_elfs.push(new_elf);
+            return;
+        }
+
+        let mut max_count = 0;
+        let mut top_elf_iter = self.top_elfs.iter().rev();
+        while max_count < 3 {
+            if let Some(elf_compare) = top_elf_iter.next() {
+                if elf_compare.calories < new_elf.calories {
+                    self.top_elfs
+                        .insert(self.top_elfs.len() - max_count, new_elf);
+                    break;
+                }
+            }
+            max_count += 1;
+        }
+    }
+
+    fn sum_of_top_three(&self) -> i32 {
+        self.top_elfs
+            .iter()
+            .rev()
+            .take(3)
+            .map(|elf| elf.calories)
+            .sum()
+    }
+
+    fn sum_and_add_elf(&mut self) {
+        let count: i32 = self.elf_calorie_stack.iter().sum();
+        let new_elf = Elf { calories: count };
+        self.elf_calorie_stack = vec![];
+        self.compare_and_add_elf(new_elf);
+    }
+}
+
+impl Default for ElfCalorieCounter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_calorie_line(input: &str, line: &str) -> Result<CalorieLine, ParseError> {
+    match line.parse::<i32>() {
+        Err(e) if e.kind() == &IntErrorKind::Empty => Ok(None),
+        Err(e) => Err(ParseError::new(input, line, CalorieError::from(e))),
+        Ok(calories) => Ok(Some(calories)),
+    }
+}
+
+/// The last elf is not followed by a blank line, so close it off explicitly.
+fn with_final_elf(input: &[CalorieLine]) -> impl Iterator<Item = &CalorieLine> {
+    input.iter().chain(iter::once(&None))
+}
+
+impl Solution for Day1 {
+    type Input = Vec<CalorieLine>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|line| parse_calorie_line(input, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i32> {
+        let mut counter = CalorieCounter::new();
+        for line in with_final_elf(input) {
+            match line {
+                None => counter.sum_and_compare_stack(),
+                Some(calories) => counter.push_to_stack(*calories),
+            }
+        }
+        Ok(counter.largest_calories)
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32> {
+        let mut counter = ElfCalorieCounter::new();
+        for line in with_final_elf(input) {
+            match line {
+                None => counter.sum_and_add_elf(),
+                Some(calories) => counter.push_to_calorie_stack(*calories),
+            }
+        }
+        Ok(counter.sum_of_top_three())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalorieError, Day1};
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    fn parse_error(input: &str) -> ParseError {
+        Day1::parse(input)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_rejects_invalid_calorie_counts() {
+        let err = parse_error("1000\n2000\n\n30x0\n");
+
+        assert!(matches!(
+            err.kind::<CalorieError>(),
+            Some(CalorieError::InvalidCalories(_))
+        ));
+        assert_eq!((err.location.line, err.location.column), (4, 1));
+    }
+}
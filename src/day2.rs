@@ -0,0 +1,201 @@
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+pub struct Day2;
+
+#[derive(Debug, Error)]
+pub enum ElfRPSError {
+    #[error("expected an opponent play of A, B or C, found {0:?}")]
+    UnknownEnemyPlay(String),
+    #[error("expected a second column of X, Y or Z, found {0:?}")]
+    UnknownColumn(String),
+    #[error("expected two columns separated by a space")]
+    MissingColumn,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub enum RPSPlay {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum RPSMatchResult {
+    Win,
+    Loss,
+    Draw,
+}
+
+/// The second column of the strategy guide. Part 1 reads it as our play,
+/// part 2 as the result we are meant to get.
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub enum RPSColumn {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Debug)]
+pub struct RPSMatch(i32);
+
+fn score_shape(shape: &RPSPlay) -> i32 {
+    match shape {
+        RPSPlay::Rock => 1,
+        RPSPlay::Paper => 2,
+        RPSPlay::Scissors => 3,
+    }
+}
+
+fn score_result(result: &RPSMatchResult) -> i32 {
+    match result {
+        RPSMatchResult::Loss => 0,
+        RPSMatchResult::Draw => 3,
+        RPSMatchResult::Win => 6,
+    }
+}
+
+fn get_required_response(enemy: &RPSPlay, desired_result: &RPSMatchResult) -> RPSPlay {
+    match (enemy, desired_result) {
+        (a, RPSMatchResult::Draw) => a.clone(),
+        (RPSPlay::Rock, RPSMatchResult::Win) => RPSPlay::Paper,
+        (RPSPlay::Rock, RPSMatchResult::Loss) => RPSPlay::Scissors,
+        (RPSPlay::Paper, RPSMatchResult::Win) => RPSPlay::Scissors,
+        (RPSPlay::Paper, RPSMatchResult::Loss) => RPSPlay::Rock,
+        (RPSPlay::Scissors, RPSMatchResult::Win) => RPSPlay::Rock,
+        (RPSPlay::Scissors, RPSMatchResult::Loss) => RPSPlay::Paper,
+    }
+}
+
+fn resolve_match(enemy: &RPSPlay, response: &RPSPlay) -> RPSMatchResult {
+    match (enemy, response) {
+        (a, b) if a == b => RPSMatchResult::Draw,
+        (RPSPlay::Rock, RPSPlay::Paper) => RPSMatchResult::Win,
+        (RPSPlay::Rock, RPSPlay::Scissors) => RPSMatchResult::Loss,
+        (RPSPlay::Paper, RPSPlay::Scissors) => RPSMatchResult::Win,
+        (RPSPlay::Paper, RPSPlay::Rock) => RPSMatchResult::Loss,
+        (RPSPlay::Scissors, RPSPlay::Rock) => RPSMatchResult::Win,
+        (RPSPlay::Scissors, RPSPlay::Paper) => RPSMatchResult::Loss,
+        (_, _) => RPSMatchResult::Draw,
+    }
+}
+
+fn map_enemy_to_play(val: &str) -> Result<RPSPlay, ElfRPSError> {
+    match val {
+        "A" => Ok(RPSPlay::Rock),
+        "B" => Ok(RPSPlay::Paper),
+        "C" => Ok(RPSPlay::Scissors),
+        _ => Err(ElfRPSError::UnknownEnemyPlay(String::from(val))),
+    }
+}
+
+fn map_str_to_column(val: &str) -> Result<RPSColumn, ElfRPSError> {
+    match val {
+        "X" => Ok(RPSColumn::X),
+        "Y" => Ok(RPSColumn::Y),
+        "Z" => Ok(RPSColumn::Z),
+        _ => Err(ElfRPSError::UnknownColumn(String::from(val))),
+    }
+}
+
+fn map_column_to_play(val: RPSColumn) -> RPSPlay {
+    match val {
+        RPSColumn::X => RPSPlay::Rock,
+        RPSColumn::Y => RPSPlay::Paper,
+        RPSColumn::Z => RPSPlay::Scissors,
+    }
+}
+
+fn map_column_to_result(val: RPSColumn) -> RPSMatchResult {
+    match val {
+        RPSColumn::X => RPSMatchResult::Loss,
+        RPSColumn::Y => RPSMatchResult::Draw,
+        RPSColumn::Z => RPSMatchResult::Win,
+    }
+}
+
+impl RPSMatch {
+    pub fn from_plays(enemy: RPSPlay, response: RPSPlay) -> Self {
+        let result = resolve_match(&enemy, &response);
+        let shape_score = score_shape(&response);
+        let match_score = score_result(&result);
+        RPSMatch(match_score + shape_score)
+    }
+
+    pub fn from_desired_result(enemy: RPSPlay, desired_result: RPSMatchResult) -> Self {
+        let response = get_required_response(&enemy, &desired_result);
+        let result = resolve_match(&enemy, &response);
+        let shape_score = score_shape(&response);
+        let match_score = score_result(&result);
+        assert!(desired_result == result, "Results are not good");
+        RPSMatch(shape_score + match_score)
+    }
+}
+
+fn parse_guide_line(input: &str, line: &str) -> Result<(RPSPlay, RPSColumn), ParseError> {
+    let (enemy, column) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(input, line, ElfRPSError::MissingColumn))?;
+    Ok((
+        map_enemy_to_play(enemy).map_err(|e| ParseError::new(input, enemy, e))?,
+        map_str_to_column(column).map_err(|e| ParseError::new(input, column, e))?,
+    ))
+}
+
+impl Solution for Day2 {
+    type Input = Vec<(RPSPlay, RPSColumn)>;
+    type Part1 = i32;
+    type Part2 = i32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|line| parse_guide_line(input, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<i32> {
+        Ok(input
+            .iter()
+            .map(|(enemy, column)| RPSMatch::from_plays(enemy.clone(), map_column_to_play(*column)))
+            .map(|m| m.0)
+            .sum())
+    }
+
+    fn part2(input: &Self::Input) -> Result<i32> {
+        Ok(input
+            .iter()
+            .map(|(enemy, column)| {
+                RPSMatch::from_desired_result(enemy.clone(), map_column_to_result(*column))
+            })
+            .map(|m| m.0)
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day2, ElfRPSError};
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    fn parse_error(input: &str) -> ParseError {
+        Day2::parse(input)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_rejects_unknown_columns() {
+        let err = parse_error("A Y\nB Q\n");
+
+        assert!(matches!(
+            err.kind::<ElfRPSError>(),
+            Some(ElfRPSError::UnknownColumn(column)) if column == "Q"
+        ));
+        assert_eq!((err.location.line, err.location.column), (2, 3));
+    }
+}
@@ -0,0 +1,100 @@
+use itertools::Itertools;
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+pub struct Day3;
+
+#[derive(Debug, Error)]
+pub enum RucksackError {
+    #[error("item {0:?} is not a letter, so it has no priority")]
+    InvalidItem(char),
+}
+
+fn char_to_priority(item: char) -> Result<u32, RucksackError> {
+    match item {
+        'a'..='z' => Ok(item as u32 - 'a' as u32 + 1),
+        'A'..='Z' => Ok(item as u32 - 'A' as u32 + 27),
+        _ => Err(RucksackError::InvalidItem(item)),
+    }
+}
+
+fn parse_rucksack(input: &str, line: &str) -> Result<String, ParseError> {
+    for (idx, item) in line.char_indices() {
+        char_to_priority(item)
+            .map_err(|e| ParseError::new(input, &line[idx..idx + item.len_utf8()], e))?;
+    }
+    Ok(String::from(line))
+}
+
+fn letters() -> impl Iterator<Item = char> {
+    ('a'..='z').chain('A'..='Z')
+}
+
+fn get_rucksack_priority(val: &str) -> u32 {
+    let (sack_a, sack_b) = val.split_at(val.len() / 2);
+
+    letters()
+        .filter(|&val| sack_a.contains(val) && sack_b.contains(val))
+        .filter_map(|val| char_to_priority(val).ok())
+        .sum()
+}
+
+fn get_badge_priority((elf_1, elf_2, elf_3): (&str, &str, &str)) -> u32 {
+    letters()
+        .filter(|&val| elf_1.contains(val) && elf_2.contains(val) && elf_3.contains(val))
+        .filter_map(|val| char_to_priority(val).ok())
+        .sum()
+}
+
+impl Solution for Day3 {
+    type Input = Vec<String>;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|line| parse_rucksack(input, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32> {
+        Ok(input.iter().map(|line| get_rucksack_priority(line)).sum())
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32> {
+        Ok(input
+            .iter()
+            .map(String::as_str)
+            .tuples::<(_, _, _)>()
+            .map(get_badge_priority)
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day3, RucksackError};
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    fn parse_error(input: &str) -> ParseError {
+        Day3::parse(input)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_rejects_items_without_a_priority() {
+        let err = parse_error("vJrwpWtwJgWr\nab1c\n");
+
+        assert!(matches!(
+            err.kind::<RucksackError>(),
+            Some(RucksackError::InvalidItem('1'))
+        ));
+        assert_eq!((err.location.line, err.location.column), (2, 3));
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+pub struct Day4;
+
+#[derive(Debug, Error)]
+pub enum ElfPairsError {
+    #[error("expected two ranges separated by a comma")]
+    MissingComma,
+    #[error("expected a range written as start-end")]
+    MissingDash,
+    #[error("invalid section number: {0}")]
+    InvalidSection(#[from] ParseIntError),
+}
+
+#[derive(Debug)]
+pub struct ElfPairs(RangeInclusive<i32>, RangeInclusive<i32>);
+
+impl ElfPairs {
+    fn is_fully_overlapped(&self) -> bool {
+        let lesser_hash_a: HashSet<i32> = self.0.clone().collect();
+        let lesser_hash_b: HashSet<i32> = self.1.clone().collect();
+
+        lesser_hash_a.is_subset(&lesser_hash_b) || lesser_hash_b.is_subset(&lesser_hash_a)
+    }
+
+    fn is_overlapped(&self) -> bool {
+        let lesser_hash_a: HashSet<i32> = self.0.clone().collect();
+        let lesser_hash_b: HashSet<i32> = self.1.clone().collect();
+
+        !lesser_hash_a.is_disjoint(&lesser_hash_b)
+    }
+}
+
+fn parse_section(input: &str, section: &str) -> Result<i32, ParseError> {
+    section
+        .parse()
+        .map_err(|e| ParseError::new(input, section, ElfPairsError::from(e)))
+}
+
+fn parse_elf_job_range(
+    input: &str,
+    job_range_str: &str,
+) -> Result<RangeInclusive<i32>, ParseError> {
+    let (start, end) = job_range_str
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(input, job_range_str, ElfPairsError::MissingDash))?;
+
+    Ok(parse_section(input, start)?..=parse_section(input, end)?)
+}
+
+fn parse_elf_job_pair(input: &str, line: &str) -> Result<ElfPairs, ParseError> {
+    let (elf_a, elf_b) = line
+        .split_once(',')
+        .ok_or_else(|| ParseError::new(input, line, ElfPairsError::MissingComma))?;
+    Ok(ElfPairs(
+        parse_elf_job_range(input, elf_a)?,
+        parse_elf_job_range(input, elf_b)?,
+    ))
+}
+
+impl Solution for Day4 {
+    type Input = Vec<ElfPairs>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|line| parse_elf_job_pair(input, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize> {
+        Ok(input
+            .iter()
+            .filter(|pair| pair.is_fully_overlapped())
+            .count())
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize> {
+        Ok(input.iter().filter(|pair| pair.is_overlapped()).count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day4, ElfPairsError};
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    fn parse_error(input: &str) -> ParseError {
+        Day4::parse(input)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_rejects_malformed_pairs() {
+        let err = parse_error("2-4,6-8\n2-4;6-8\n");
+        assert!(matches!(
+            err.kind::<ElfPairsError>(),
+            Some(ElfPairsError::MissingComma)
+        ));
+        assert_eq!((err.location.line, err.location.column), (2, 1));
+
+        let err = parse_error("2-4,6-x\n");
+        assert!(matches!(
+            err.kind::<ElfPairsError>(),
+            Some(ElfPairsError::InvalidSection(_))
+        ));
+        assert_eq!((err.location.line, err.location.column), (1, 7));
+    }
+}
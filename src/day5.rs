@@ -0,0 +1,979 @@
+use std::fmt;
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+lazy_static! {
+    static ref MOVE_REGEX: Regex = Regex::new(
+        r"move (?P<move_num>[0-9]+) from (?P<from_stack>[0-9]+) to (?P<to_stack>[0-9]+)"
+    )
+    .expect("Day 5 - Inputs: Effed up the regex");
+}
+
+pub struct Day5;
+
+#[derive(Debug, Error)]
+pub enum ElfCrateParseError {
+    #[error("expected the stacks and the moves separated by a blank line")]
+    MissingMoves,
+    #[error("expected a line of stack numbers under the stack drawing")]
+    MissingLabels,
+    #[error("expected stack number {expected}, found {found:?}")]
+    UnexpectedLabel { expected: usize, found: String },
+    #[error("crate is not above any numbered stack")]
+    UnlabelledStack,
+    #[error("expected a crate like \"[Z]\" or an empty slot")]
+    MalformedCrate,
+    #[error("crate labels can not be empty")]
+    EmptyCrate,
+    #[error("crate is floating above an empty slot")]
+    FloatingCrate,
+    #[error("expected a move like \"move 1 from 2 to 3\"")]
+    InvalidMove,
+    #[error("invalid number: {0}")]
+    InvalidNumber(#[from] ParseIntError),
+}
+
+/// A move that can't be made against the current stacks. `index` is the
+/// 1-based position of the move in the list.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum CrateMoveError {
+    #[error("move {index}: there is no stack {stack}, only 1 to {stacks}")]
+    UnknownStack {
+        index: usize,
+        stack: usize,
+        stacks: usize,
+    },
+    #[error(
+        "move {index}: can't take {requested} crates from stack {stack}, it only has {available}"
+    )]
+    InsufficientCrates {
+        index: usize,
+        stack: usize,
+        requested: usize,
+        available: usize,
+    },
+    #[error("move {index}: stack {stack} is both the source and the destination")]
+    SameStack { index: usize, stack: usize },
+}
+
+impl CrateMoveError {
+    pub fn index(&self) -> usize {
+        match self {
+            CrateMoveError::UnknownStack { index, .. }
+            | CrateMoveError::InsufficientCrates { index, .. }
+            | CrateMoveError::SameStack { index, .. } => *index,
+        }
+    }
+}
+
+/// Checks `elf_move`, the `index`th move, against `stacks` stacks where
+/// `height` gives the number of crates on a 0-based stack.
+pub(crate) fn validate_move(
+    index: usize,
+    elf_move: &ElfCrateMove,
+    stacks: usize,
+    height: impl Fn(usize) -> usize,
+) -> Result<(), CrateMoveError> {
+    for stack in [elf_move.from_stack, elf_move.to_stack] {
+        if stack == 0 || stack > stacks {
+            return Err(CrateMoveError::UnknownStack {
+                index,
+                stack,
+                stacks,
+            });
+        }
+    }
+    if elf_move.from_stack == elf_move.to_stack {
+        return Err(CrateMoveError::SameStack {
+            index,
+            stack: elf_move.from_stack,
+        });
+    }
+
+    let available = height(elf_move.from_stack - 1);
+    if available < elf_move.move_num {
+        return Err(CrateMoveError::InsufficientCrates {
+            index,
+            stack: elf_move.from_stack,
+            requested: elf_move.move_num,
+            available,
+        });
+    }
+    Ok(())
+}
+
+/// What to do with a move that fails validation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MoveMode {
+    /// Stop at the first invalid move.
+    #[default]
+    Strict,
+    /// Make as much of the move as possible: take only the crates that are
+    /// there, and skip moves to or from unknown stacks or onto their own
+    /// stack.
+    Lenient,
+}
+
+#[derive(Debug, Error)]
+#[error("unknown crane {0:?}, expected 9000, 9001, capacity-N or alternating-N")]
+pub struct UnknownCraneError(String);
+
+/// A crane model: how the crates of one move end up on the destination
+/// stack.
+pub trait CrateMover {
+    /// The name the crane is picked by, see [`crate_mover`].
+    fn name(&self) -> String;
+
+    /// Makes as much of `elf_move` as the stacks allow. Validation is up to
+    /// the caller, see [`ElfCrateStacks::check_move`].
+    fn apply(&self, stacks: &mut ElfCrateStacks, elf_move: &ElfCrateMove);
+}
+
+/// Moves crates one at a time, so a batch lands reversed.
+pub struct CrateMover9000;
+
+/// Moves a whole batch at once, keeping its order.
+pub struct CrateMover9001;
+
+/// The original CrateMover 9000 loop, popping and pushing one crate per
+/// step. Same result as [`CrateMover9000`]; kept as the baseline for
+/// `aoc scale`.
+pub struct OneByOneCrateMover9000;
+
+/// Lifts at most `capacity` crates at a time, each lift keeping its order,
+/// and with `alternate` set flips every other lift of a move.
+pub struct BatchCrateMover {
+    pub capacity: usize,
+    pub alternate: bool,
+}
+
+impl CrateMover for CrateMover9000 {
+    fn name(&self) -> String {
+        String::from("9000")
+    }
+
+    fn apply(&self, stacks: &mut ElfCrateStacks, elf_move: &ElfCrateMove) {
+        if let Some((mut moved_crates, to)) = stacks.lift(elf_move) {
+            moved_crates.reverse();
+            stacks.stacks[to].append(&mut moved_crates);
+        }
+    }
+}
+
+impl CrateMover for OneByOneCrateMover9000 {
+    fn name(&self) -> String {
+        String::from("9000, one crate at a time")
+    }
+
+    fn apply(&self, stacks: &mut ElfCrateStacks, elf_move: &ElfCrateMove) {
+        let from = elf_move.from_stack.wrapping_sub(1);
+        let to = elf_move.to_stack.wrapping_sub(1);
+        if from == to || to >= stacks.stacks.len() {
+            return;
+        }
+        for _ in 0..elf_move.move_num {
+            let Some(label) = stacks.stacks.get_mut(from).and_then(Vec::pop) else {
+                break;
+            };
+            stacks.stacks[to].push(label);
+        }
+    }
+}
+
+impl CrateMover for CrateMover9001 {
+    fn name(&self) -> String {
+        String::from("9001")
+    }
+
+    fn apply(&self, stacks: &mut ElfCrateStacks, elf_move: &ElfCrateMove) {
+        if let Some((mut moved_crates, to)) = stacks.lift(elf_move) {
+            stacks.stacks[to].append(&mut moved_crates);
+        }
+    }
+}
+
+impl CrateMover for BatchCrateMover {
+    fn name(&self) -> String {
+        match self.alternate {
+            true => format!("alternating-{}", self.capacity),
+            false => format!("capacity-{}", self.capacity),
+        }
+    }
+
+    fn apply(&self, stacks: &mut ElfCrateStacks, elf_move: &ElfCrateMove) {
+        let Some((moved_crates, to)) = stacks.lift(elf_move) else {
+            return;
+        };
+
+        // The top of the source stack is lifted first, so walk the batches
+        // from the end.
+        let capacity = self.capacity.max(1);
+        let lifts = moved_crates.rchunks(capacity);
+        for (lift_num, lift) in lifts.enumerate() {
+            if self.alternate && lift_num % 2 == 1 {
+                stacks.stacks[to].extend(lift.iter().rev().cloned());
+            } else {
+                stacks.stacks[to].extend_from_slice(lift);
+            }
+        }
+    }
+}
+
+/// Picks a crane by name: `9000`, `9001`, `capacity-N` for a crane that
+/// lifts at most N crates at a time, or `alternating-N` for one that also
+/// flips every other lift.
+pub fn crate_mover(name: &str) -> Result<Box<dyn CrateMover>, UnknownCraneError> {
+    let batch = |capacity: &str, alternate| match capacity.parse() {
+        Ok(capacity) if capacity > 0 => Ok(Box::new(BatchCrateMover {
+            capacity,
+            alternate,
+        }) as Box<dyn CrateMover>),
+        _ => Err(UnknownCraneError(String::from(name))),
+    };
+
+    match name {
+        "9000" => Ok(Box::new(CrateMover9000)),
+        "9001" => Ok(Box::new(CrateMover9001)),
+        _ => match name.split_once('-') {
+            Some(("capacity", capacity)) => batch(capacity, false),
+            Some(("alternating", capacity)) => batch(capacity, true),
+            _ => Err(UnknownCraneError(String::from(name))),
+        },
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfCrateStacks {
+    stacks: Vec<Vec<String>>,
+    journal: Vec<JournalEntry>,
+}
+
+/// What one applied move changed, enough to put it back exactly whatever
+/// the crane did with the crates.
+#[derive(Debug, Clone)]
+struct JournalEntry {
+    elf_move: ElfCrateMove,
+    /// The crates taken off the source stack, bottom crate first.
+    lifted: Vec<String>,
+    /// Height of the destination stack before the move.
+    to_len: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfCrateStackTops {
+    stack_tops: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfCrateMove {
+    move_num: usize,
+    from_stack: usize,
+    to_stack: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct ElfCrateMoves(Vec<ElfCrateMove>);
+
+impl ElfCrateMove {
+    pub fn new(move_num: usize, from_stack: usize, to_stack: usize) -> Self {
+        Self {
+            move_num,
+            from_stack,
+            to_stack,
+        }
+    }
+
+    pub fn move_num(&self) -> usize {
+        self.move_num
+    }
+
+    /// The 1-based stack the crates are taken from.
+    pub fn from_stack(&self) -> usize {
+        self.from_stack
+    }
+
+    /// The 1-based stack the crates are put on.
+    pub fn to_stack(&self) -> usize {
+        self.to_stack
+    }
+}
+
+impl fmt::Display for ElfCrateMove {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.move_num, self.from_stack, self.to_stack
+        )
+    }
+}
+
+/// One move per line, in the format the move list is parsed from.
+impl fmt::Display for ElfCrateMoves {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = self.0.iter().map(ElfCrateMove::to_string).collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+impl ElfCrateMoves {
+    pub fn moves(&self) -> &[ElfCrateMove] {
+        &self.0
+    }
+
+    /// The moves that take the final stacks back to the starting ones: the
+    /// list reversed with every move's stacks swapped, run through the same
+    /// crane. Exact for the 9000 and 9001 as long as every move was valid;
+    /// use [`ElfCrateStacks::inverse_moves`] when some weren't, and
+    /// [`ElfCrateStacks::undo`] for any other crane.
+    pub fn inverse(&self) -> ElfCrateMoves {
+        ElfCrateMoves(
+            self.0
+                .iter()
+                .rev()
+                .map(|elf_move| {
+                    ElfCrateMove::new(elf_move.move_num, elf_move.to_stack, elf_move.from_stack)
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<Vec<ElfCrateMove>> for ElfCrateMoves {
+    fn from(moves: Vec<ElfCrateMove>) -> Self {
+        ElfCrateMoves(moves)
+    }
+}
+
+impl From<Vec<Vec<String>>> for ElfCrateStacks {
+    /// Builds stacks from labels listed bottom crate first.
+    fn from(stacks: Vec<Vec<String>>) -> Self {
+        ElfCrateStacks {
+            stacks,
+            journal: vec![],
+        }
+    }
+}
+
+impl ElfCrateStacks {
+    /// Every stack, bottom crate first.
+    pub fn stacks(&self) -> &[Vec<String>] {
+        &self.stacks
+    }
+
+    pub fn get_tops_of_stacks(&self) -> ElfCrateStackTops {
+        let stack_tops: Vec<String> = self
+            .stacks
+            .iter()
+            .map(|stack| stack.last().map(String::as_str).unwrap_or(" "))
+            .map(String::from)
+            .collect();
+
+        ElfCrateStackTops { stack_tops }
+    }
+
+    /// Checks `elf_move`, the `index`th move, against the current stacks.
+    pub fn check_move(&self, index: usize, elf_move: &ElfCrateMove) -> Result<(), CrateMoveError> {
+        validate_move(index, elf_move, self.stacks.len(), |stack| {
+            self.stacks[stack].len()
+        })
+    }
+
+    /// Takes up to `move_num` crates off the source stack, bottom crate
+    /// first, along with the destination index. Moves that can't be made at
+    /// all take nothing.
+    fn lift(&mut self, elf_move: &ElfCrateMove) -> Option<(Vec<String>, usize)> {
+        let from = elf_move.from_stack.checked_sub(1)?;
+        let to = elf_move.to_stack.checked_sub(1)?;
+        if from == to || to >= self.stacks.len() {
+            return None;
+        }
+
+        let from_stack = self.stacks.get_mut(from)?;
+        let split_point = from_stack.len().saturating_sub(elf_move.move_num);
+        Some((from_stack.drain(split_point..).collect(), to))
+    }
+
+    /// Checks `elf_move`, the `index`th move, and runs it through `crane`
+    /// without journalling it. In lenient mode an invalid move is made as far
+    /// as possible and its problem returned as a warning.
+    pub fn make_move(
+        &mut self,
+        index: usize,
+        elf_move: &ElfCrateMove,
+        crane: &dyn CrateMover,
+        mode: MoveMode,
+    ) -> Result<Option<CrateMoveError>, CrateMoveError> {
+        let warning = match (self.check_move(index, elf_move), mode) {
+            (Err(err), MoveMode::Strict) => return Err(err),
+            (result, _) => result.err(),
+        };
+        crane.apply(self, elf_move);
+        Ok(warning)
+    }
+
+    /// Like [`ElfCrateStacks::make_move`], but records the move in the undo
+    /// journal.
+    pub fn apply_move(
+        &mut self,
+        index: usize,
+        elf_move: &ElfCrateMove,
+        crane: &dyn CrateMover,
+        mode: MoveMode,
+    ) -> Result<Option<CrateMoveError>, CrateMoveError> {
+        let from = elf_move.from_stack.wrapping_sub(1);
+        let to = elf_move.to_stack.wrapping_sub(1);
+        let entry = match (self.stacks.get(from), self.stacks.get(to)) {
+            (Some(from_stack), Some(to_stack)) if from != to => JournalEntry {
+                elf_move: elf_move.clone(),
+                lifted: from_stack[from_stack.len().saturating_sub(elf_move.move_num)..].to_vec(),
+                to_len: to_stack.len(),
+            },
+            _ => JournalEntry {
+                elf_move: elf_move.clone(),
+                lifted: vec![],
+                to_len: 0,
+            },
+        };
+
+        let warning = self.make_move(index, elf_move, crane, mode)?;
+        self.journal.push(entry);
+        Ok(warning)
+    }
+
+    /// Runs every move through `crane`. In lenient mode the problems that
+    /// were worked around are returned instead of stopping at the first.
+    pub fn apply_moves(
+        &mut self,
+        elf_moves: &ElfCrateMoves,
+        crane: &dyn CrateMover,
+        mode: MoveMode,
+    ) -> Result<Vec<CrateMoveError>, CrateMoveError> {
+        let mut warnings = vec![];
+        for (idx, elf_move) in elf_moves.0.iter().enumerate() {
+            warnings.extend(self.apply_move(idx + 1, elf_move, crane, mode)?);
+        }
+        Ok(warnings)
+    }
+
+    /// Forgets every journalled move, keeping the stacks as they are.
+    pub fn clear_journal(&mut self) {
+        self.journal.clear();
+    }
+
+    /// Number of applied moves that can be undone.
+    pub fn journal_len(&self) -> usize {
+        self.journal.len()
+    }
+
+    /// Reverts the last applied move, returning it.
+    pub fn undo(&mut self) -> Option<ElfCrateMove> {
+        let entry = self.journal.pop()?;
+        if !entry.lifted.is_empty() {
+            self.stacks[entry.elf_move.to_stack - 1].truncate(entry.to_len);
+            self.stacks[entry.elf_move.from_stack - 1].extend(entry.lifted);
+        }
+        Some(entry.elf_move)
+    }
+
+    /// Reverts every journalled move, back to the stacks as parsed.
+    pub fn undo_all(&mut self) -> usize {
+        let undone = self.journal.len();
+        while self.undo().is_some() {}
+        undone
+    }
+
+    /// The moves that take the stacks back to before the journalled ones,
+    /// using how many crates each move really took. Exact for the 9000 and
+    /// 9001.
+    pub fn inverse_moves(&self) -> ElfCrateMoves {
+        ElfCrateMoves(
+            self.journal
+                .iter()
+                .rev()
+                .filter(|entry| !entry.lifted.is_empty())
+                .map(|entry| {
+                    ElfCrateMove::new(
+                        entry.lifted.len(),
+                        entry.elf_move.to_stack,
+                        entry.elf_move.from_stack,
+                    )
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Width of one crate slot in the drawing, `[Z]` plus the gap after it.
+const SLOT_WIDTH: usize = 4;
+
+/// Parses the stack drawing in `section`, a slice of the full `input`: rows
+/// of `[Z]` crates, top row first, over a ` 1   2   3 ` label line.
+fn parse_stacks(input: &str, section: &str) -> Result<ElfCrateStacks, ParseError> {
+    let mut rows: Vec<&str> = section.lines().collect();
+    let labels = rows
+        .pop()
+        .filter(|line| line.trim_start().starts_with(|c: char| c.is_ascii_digit()))
+        .ok_or_else(|| ParseError::new(input, section, ElfCrateParseError::MissingLabels))?;
+
+    for (idx, label) in labels.split_whitespace().enumerate() {
+        if label != (idx + 1).to_string() {
+            return Err(ParseError::new(
+                input,
+                label,
+                ElfCrateParseError::UnexpectedLabel {
+                    expected: idx + 1,
+                    found: String::from(label),
+                },
+            ));
+        }
+    }
+
+    let mut stacks = vec![vec![]; labels.split_whitespace().count()];
+    for (level, row) in rows.iter().rev().enumerate() {
+        for (idx, slot) in slots(input, row)?.into_iter().enumerate() {
+            if slot.trim().is_empty() {
+                continue;
+            }
+            let stack = stacks
+                .get_mut(idx)
+                .ok_or_else(|| ParseError::new(input, slot, ElfCrateParseError::UnlabelledStack))?;
+            let label = slot
+                .strip_prefix('[')
+                .and_then(|slot| slot.strip_suffix(']'))
+                .ok_or_else(|| ParseError::new(input, slot, ElfCrateParseError::MalformedCrate))?;
+            if label.trim().is_empty() {
+                return Err(ParseError::new(input, slot, ElfCrateParseError::EmptyCrate));
+            }
+            if stack.len() != level {
+                return Err(ParseError::new(
+                    input,
+                    slot,
+                    ElfCrateParseError::FloatingCrate,
+                ));
+            }
+            stack.push(String::from(label));
+        }
+    }
+
+    Ok(ElfCrateStacks::from(stacks))
+}
+
+/// Splits a drawing row into its crate slots, dropping the gap after each.
+fn slots<'a>(input: &str, row: &'a str) -> Result<Vec<&'a str>, ParseError> {
+    (0..row.len())
+        .step_by(SLOT_WIDTH)
+        .map(|start| {
+            row.get(start..row.len().min(start + SLOT_WIDTH - 1))
+                .ok_or_else(|| ParseError::new(input, row, ElfCrateParseError::MalformedCrate))
+        })
+        .collect()
+}
+
+/// Parses the move list in `section`, a slice of the full `input`.
+fn parse_moves(input: &str, section: &str) -> Result<ElfCrateMoves, ParseError> {
+    let parse_num = |num: &str| {
+        num.parse::<usize>()
+            .map_err(|e| ParseError::new(input, num, ElfCrateParseError::from(e)))
+    };
+
+    let move_vec = section
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let cap = MOVE_REGEX
+                .captures(line)
+                .ok_or_else(|| ParseError::new(input, line, ElfCrateParseError::InvalidMove))?;
+            Ok(ElfCrateMove {
+                move_num: parse_num(&cap["move_num"])?,
+                from_stack: parse_num(&cap["from_stack"])?,
+                to_stack: parse_num(&cap["to_stack"])?,
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(ElfCrateMoves(move_vec))
+}
+
+impl FromStr for ElfCrateStacks {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_stacks(s, s)
+    }
+}
+
+/// Draws the stacks in the puzzle's own format, which [`ElfCrateStacks`]
+/// parses back.
+impl fmt::Display for ElfCrateStacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+        for level in (0..height).rev() {
+            let row: Vec<String> = self
+                .stacks
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(label) => format!("[{}]", label),
+                    None => String::from("   "),
+                })
+                .collect();
+            writeln!(f, "{}", row.join(" "))?;
+        }
+        let labels: Vec<String> = (1..=self.stacks.len())
+            .map(|idx| format!(" {} ", idx))
+            .collect();
+        write!(f, "{}", labels.join(" "))
+    }
+}
+
+impl fmt::Display for ElfCrateStackTops {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let stack_top_str: String = self.stack_tops.concat();
+        write!(f, "{}", stack_top_str)
+    }
+}
+
+impl FromStr for ElfCrateMoves {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_moves(s, s)
+    }
+}
+
+/// Runs the whole move list through `crane` on a copy of the stacks,
+/// without journalling the moves.
+pub fn run_crane(
+    elf_stacks: &ElfCrateStacks,
+    elf_moves: &ElfCrateMoves,
+    crane: &dyn CrateMover,
+    mode: MoveMode,
+) -> Result<ElfCrateStacks, CrateMoveError> {
+    let mut stacks_workspace = elf_stacks.clone();
+    stacks_workspace.clear_journal();
+    for (idx, elf_move) in elf_moves.0.iter().enumerate() {
+        stacks_workspace.make_move(idx + 1, elf_move, crane, mode)?;
+    }
+    Ok(stacks_workspace)
+}
+
+impl Solution for Day5 {
+    type Input = (ElfCrateStacks, ElfCrateMoves);
+    type Part1 = ElfCrateStackTops;
+    type Part2 = ElfCrateStackTops;
+
+    /// Expects the stack drawing and the move list in one string, separated
+    /// by a blank line.
+    fn parse(input: &str) -> Result<Self::Input> {
+        let (stacks, moves) = input.split_once("\n\n").ok_or_else(|| {
+            ParseError::new(
+                input,
+                &input[input.len()..],
+                ElfCrateParseError::MissingMoves,
+            )
+        })?;
+        Ok((parse_stacks(input, stacks)?, parse_moves(input, moves)?))
+    }
+
+    fn part1((elf_stacks, elf_moves): &Self::Input) -> Result<ElfCrateStackTops> {
+        Ok(
+            run_crane(elf_stacks, elf_moves, &CrateMover9000, MoveMode::Strict)?
+                .get_tops_of_stacks(),
+        )
+    }
+
+    fn part2((elf_stacks, elf_moves): &Self::Input) -> Result<ElfCrateStackTops> {
+        Ok(
+            run_crane(elf_stacks, elf_moves, &CrateMover9001, MoveMode::Strict)?
+                .get_tops_of_stacks(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{
+        crate_mover, run_crane, BatchCrateMover, CrateMoveError, CrateMover, CrateMover9000,
+        CrateMover9001, Day5, ElfCrateMove, ElfCrateMoves, ElfCrateParseError, ElfCrateStacks,
+        MoveMode, OneByOneCrateMover9000,
+    };
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    const EXAMPLE_STACKS: &str = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+
+    #[test]
+    fn it_parses_the_stack_drawing() {
+        let stacks: ElfCrateStacks = EXAMPLE_STACKS.parse().unwrap();
+
+        assert_eq!(
+            stacks.stacks(),
+            &[vec!["Z", "N"], vec!["M", "C", "D"], vec!["P"]]
+        );
+    }
+
+    #[test]
+    fn it_round_trips_the_stack_drawing() {
+        let stacks: ElfCrateStacks = EXAMPLE_STACKS.parse().unwrap();
+
+        assert_eq!(stacks.to_string(), EXAMPLE_STACKS);
+        assert_eq!(
+            stacks
+                .to_string()
+                .parse::<ElfCrateStacks>()
+                .unwrap()
+                .stacks(),
+            stacks.stacks()
+        );
+    }
+
+    #[test]
+    fn it_parses_a_combined_input() {
+        let input = format!(
+            "{}\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\n",
+            EXAMPLE_STACKS
+        );
+        let parsed = Day5::parse(&input).unwrap();
+
+        assert_eq!(Day5::part1(&parsed).unwrap().to_string(), " CZ");
+    }
+
+    #[test]
+    fn it_rejects_floating_crates() {
+        let err = "[D]    \n    [C]\n 1   2 "
+            .parse::<ElfCrateStacks>()
+            .unwrap_err();
+
+        assert!(matches!(
+            err.kind::<ElfCrateParseError>(),
+            Some(ElfCrateParseError::FloatingCrate)
+        ));
+        assert_eq!((err.location.line, err.location.column), (1, 1));
+    }
+
+    #[test]
+    fn it_rejects_crates_without_a_stack_number() {
+        let err: ParseError = "[A] [B]\n 1 ".parse::<ElfCrateStacks>().unwrap_err();
+
+        assert!(matches!(
+            err.kind::<ElfCrateParseError>(),
+            Some(ElfCrateParseError::UnlabelledStack)
+        ));
+    }
+
+    #[test]
+    fn it_reports_the_first_invalid_move_in_strict_mode() {
+        let stacks: ElfCrateStacks = EXAMPLE_STACKS.parse().unwrap();
+        let moves: ElfCrateMoves = "move 1 from 2 to 1\nmove 4 from 1 to 3\nmove 1 from 4 to 1"
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            run_crane(&stacks, &moves, &CrateMover9001, MoveMode::Strict).unwrap_err(),
+            CrateMoveError::InsufficientCrates {
+                index: 2,
+                stack: 1,
+                requested: 4,
+                available: 3
+            }
+        );
+    }
+
+    #[test]
+    fn it_works_around_invalid_moves_in_lenient_mode() {
+        let stacks: ElfCrateStacks = EXAMPLE_STACKS.parse().unwrap();
+        let moves: ElfCrateMoves = "move 5 from 2 to 1\nmove 1 from 0 to 1\nmove 1 from 3 to 3"
+            .parse()
+            .unwrap();
+        let mut lenient = stacks.clone();
+        let warnings = lenient
+            .apply_moves(&moves, &CrateMover9000, MoveMode::Lenient)
+            .unwrap();
+
+        assert_eq!(
+            warnings
+                .iter()
+                .map(CrateMoveError::index)
+                .collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(matches!(
+            warnings[1],
+            CrateMoveError::UnknownStack { stack: 0, .. }
+        ));
+        assert!(matches!(
+            warnings[2],
+            CrateMoveError::SameStack { stack: 3, .. }
+        ));
+        assert_eq!(lenient.get_tops_of_stacks().to_string(), "M P");
+        assert_eq!(
+            run_crane(&stacks, &moves, &CrateMover9000, MoveMode::Lenient)
+                .unwrap()
+                .stacks(),
+            lenient.stacks()
+        );
+    }
+
+    #[test]
+    fn it_picks_cranes_by_name() {
+        let input = format!(
+            "{}\n\nmove 1 from 2 to 1\nmove 3 from 1 to 3\nmove 2 from 2 to 1\nmove 1 from 1 to 2",
+            EXAMPLE_STACKS
+        );
+        let (stacks, moves) = Day5::parse(&input).unwrap();
+        let tops = |name: &str| {
+            let crane = crate_mover(name).unwrap();
+            assert_eq!(crane.name(), name);
+            run_crane(&stacks, &moves, crane.as_ref(), MoveMode::Strict)
+                .unwrap()
+                .get_tops_of_stacks()
+                .to_string()
+        };
+
+        assert_eq!(tops("9000"), "CMZ");
+        assert_eq!(tops("9001"), "MCD");
+        assert_eq!(tops("capacity-1"), "CMZ");
+        assert_eq!(tops("capacity-3"), "MCD");
+        assert_eq!(tops("capacity-2"), "MCZ");
+        assert_eq!(tops("alternating-1"), "CMZ");
+        assert!(crate_mover("capacity-0").is_err());
+        assert!(crate_mover("9002").is_err());
+    }
+
+    #[test]
+    fn it_flips_every_other_lift() {
+        let mut stacks: ElfCrateStacks = "[D]\n[C]\n[B]\n[A]\n 1   2 ".parse().unwrap();
+        let moves: ElfCrateMoves = "move 4 from 1 to 2".parse().unwrap();
+        let crane = BatchCrateMover {
+            capacity: 2,
+            alternate: true,
+        };
+        stacks
+            .apply_moves(&moves, &crane, MoveMode::Strict)
+            .unwrap();
+
+        assert_eq!(stacks.stacks()[1], vec!["C", "D", "B", "A"]);
+    }
+
+    #[test]
+    fn it_undoes_moves_one_at_a_time() {
+        let mut stacks: ElfCrateStacks = EXAMPLE_STACKS.parse().unwrap();
+        let moves: ElfCrateMoves = "move 1 from 2 to 1\nmove 3 from 1 to 3".parse().unwrap();
+        stacks
+            .apply_moves(&moves, &CrateMover9000, MoveMode::Strict)
+            .unwrap();
+
+        assert_eq!(stacks.journal_len(), 2);
+        assert_eq!(stacks.undo().unwrap().to_string(), "move 3 from 1 to 3");
+        assert_eq!(
+            stacks.stacks(),
+            &[vec!["Z", "N", "D"], vec!["M", "C"], vec!["P"]]
+        );
+        assert_eq!(stacks.undo_all(), 1);
+        assert_eq!(stacks.to_string(), EXAMPLE_STACKS);
+        assert!(stacks.undo().is_none());
+    }
+
+    fn arb_stacks() -> impl Strategy<Value = ElfCrateStacks> {
+        prop::collection::vec(prop::collection::vec("[A-Z]", 0..6), 1..6)
+            .prop_map(ElfCrateStacks::from)
+    }
+
+    fn arb_moves() -> impl Strategy<Value = Vec<ElfCrateMove>> {
+        prop::collection::vec(
+            (0usize..7, 0usize..7, 0usize..7)
+                .prop_map(|(num, from, to)| ElfCrateMove::new(num, from, to)),
+            0..20,
+        )
+    }
+
+    fn arb_crane() -> impl Strategy<Value = &'static str> {
+        prop_oneof![
+            Just("9000"),
+            Just("9001"),
+            Just("capacity-2"),
+            Just("alternating-2")
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn undo_all_restores_the_start(
+            start in arb_stacks(),
+            moves in arb_moves(),
+            crane in arb_crane(),
+        ) {
+            let crane = crate_mover(crane).unwrap();
+            let mut stacks = start.clone();
+            stacks
+                .apply_moves(&ElfCrateMoves::from(moves), crane.as_ref(), MoveMode::Lenient)
+                .unwrap();
+            stacks.undo_all();
+
+            prop_assert_eq!(stacks.stacks(), start.stacks());
+        }
+
+        #[test]
+        fn one_by_one_matches_the_bulk_9000(start in arb_stacks(), moves in arb_moves()) {
+            let moves = ElfCrateMoves::from(moves);
+            let bulk = run_crane(&start, &moves, &CrateMover9000, MoveMode::Lenient).unwrap();
+            let one_by_one =
+                run_crane(&start, &moves, &OneByOneCrateMover9000, MoveMode::Lenient).unwrap();
+
+            prop_assert_eq!(one_by_one.stacks(), bulk.stacks());
+        }
+
+        #[test]
+        fn journalled_inverse_moves_restore_the_start(
+            start in arb_stacks(),
+            moves in arb_moves(),
+            lifo in any::<bool>(),
+        ) {
+            let crane: &dyn CrateMover = match lifo {
+                true => &CrateMover9000,
+                false => &CrateMover9001,
+            };
+            let mut stacks = start.clone();
+            stacks
+                .apply_moves(&ElfCrateMoves::from(moves), crane, MoveMode::Lenient)
+                .unwrap();
+            let inverse = stacks.inverse_moves();
+            stacks.apply_moves(&inverse, crane, MoveMode::Strict).unwrap();
+
+            prop_assert_eq!(stacks.stacks(), start.stacks());
+        }
+
+        #[test]
+        fn inverse_of_valid_moves_restores_the_start(
+            start in arb_stacks(),
+            moves in arb_moves(),
+            lifo in any::<bool>(),
+        ) {
+            let crane: &dyn CrateMover = match lifo {
+                true => &CrateMover9000,
+                false => &CrateMover9001,
+            };
+            // Keep only the moves that are valid where they land.
+            let mut stacks = start.clone();
+            let valid: Vec<ElfCrateMove> = moves
+                .into_iter()
+                .filter(|elf_move| {
+                    stacks.apply_move(1, elf_move, crane, MoveMode::Strict).is_ok()
+                })
+                .collect();
+            stacks
+                .apply_moves(&ElfCrateMoves::from(valid).inverse(), crane, MoveMode::Strict)
+                .unwrap();
+
+            prop_assert_eq!(stacks.stacks(), start.stacks());
+        }
+    }
+}
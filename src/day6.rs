@@ -0,0 +1,502 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::io::{self, Read};
+
+use thiserror::Error;
+
+use crate::{Result, Solution};
+
+pub const SOP_SIZE: usize = 4;
+pub const SOM_SIZE: usize = 14;
+
+pub struct Day6;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MarkerError {
+    #[error("no run of {0} different characters in the datastream")]
+    NotFound(usize),
+}
+
+/// What the datastream is made of when looking for a marker, and what
+/// marker positions count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MarkerUnit {
+    /// Raw bytes, so a multi-byte character is several symbols.
+    Bytes,
+    /// Unicode scalar values.
+    #[default]
+    Chars,
+}
+
+/// How many of each symbol are in the window.
+trait Tally<T> {
+    /// Adds one `symbol` and returns how many there are now.
+    fn add(&mut self, symbol: T) -> u32;
+    /// Removes one `symbol` and returns how many are left.
+    fn remove(&mut self, symbol: T) -> u32;
+}
+
+impl Tally<u8> for [u32; 256] {
+    fn add(&mut self, symbol: u8) -> u32 {
+        self[symbol as usize] += 1;
+        self[symbol as usize]
+    }
+
+    fn remove(&mut self, symbol: u8) -> u32 {
+        self[symbol as usize] -= 1;
+        self[symbol as usize]
+    }
+}
+
+impl<T: Eq + Hash> Tally<T> for HashMap<T, u32> {
+    fn add(&mut self, symbol: T) -> u32 {
+        let count = self.entry(symbol).or_insert(0);
+        *count += 1;
+        *count
+    }
+
+    fn remove(&mut self, symbol: T) -> u32 {
+        let count = self.entry(symbol).or_insert(1);
+        *count -= 1;
+        *count
+    }
+}
+
+/// Slides a `marker_size` window over `symbols`, keeping a count of each
+/// symbol in it and of how many are different, so every step is O(1).
+fn scan<T: Copy>(symbols: &[T], marker_size: usize, tally: &mut impl Tally<T>) -> Option<usize> {
+    if marker_size == 0 {
+        return Some(0);
+    }
+
+    let mut distinct = 0;
+    for (idx, &symbol) in symbols.iter().enumerate() {
+        if tally.add(symbol) == 1 {
+            distinct += 1;
+        }
+        if idx >= marker_size && tally.remove(symbols[idx - marker_size]) == 0 {
+            distinct -= 1;
+        }
+        if distinct == marker_size {
+            return Some(idx + 1);
+        }
+    }
+    None
+}
+
+/// [`find_marker`] over raw bytes.
+pub fn find_byte_marker(input: &[u8], marker_size: usize) -> Option<usize> {
+    scan(input, marker_size, &mut [0; 256])
+}
+
+/// Number of symbols read up to and including the first run of
+/// `marker_size` different ones, or `None` if there is no such run.
+pub fn find_marker(input: &str, marker_size: usize, unit: MarkerUnit) -> Option<usize> {
+    match unit {
+        MarkerUnit::Bytes => find_byte_marker(input.as_bytes(), marker_size),
+        MarkerUnit::Chars => {
+            let chars: Vec<char> = input.chars().collect();
+            scan(&chars, marker_size, &mut HashMap::new())
+        }
+    }
+}
+
+/// Bytes read from the underlying reader at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    StartOfPacket,
+    StartOfMessage,
+}
+
+impl fmt::Display for MarkerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MarkerKind::StartOfPacket => write!(f, "start-of-packet"),
+            MarkerKind::StartOfMessage => write!(f, "start-of-message"),
+        }
+    }
+}
+
+/// A marker found in a stream. `offset` counts the bytes read up to and
+/// including the marker, like the puzzle answers do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MarkerEvent {
+    pub kind: MarkerKind,
+    pub offset: u64,
+}
+
+impl fmt::Display for MarkerEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.kind, self.offset)
+    }
+}
+
+/// The last `size` bytes of a stream in a ring buffer, with a count of each
+/// byte value in it.
+struct MarkerWindow {
+    ring: Vec<u8>,
+    size: usize,
+    head: usize,
+    counts: [u32; 256],
+    distinct: usize,
+}
+
+impl MarkerWindow {
+    fn new(size: usize) -> Self {
+        Self {
+            ring: Vec::with_capacity(size),
+            size,
+            head: 0,
+            counts: [0; 256],
+            distinct: 0,
+        }
+    }
+
+    /// Adds `byte`, returning true when the window is a marker. The window
+    /// then starts over, so markers never overlap.
+    fn push(&mut self, byte: u8) -> bool {
+        if self.size == 0 {
+            return false;
+        }
+        if self.ring.len() == self.size {
+            let oldest = std::mem::replace(&mut self.ring[self.head], byte);
+            self.head = (self.head + 1) % self.size;
+            if self.counts.remove(oldest) == 0 {
+                self.distinct -= 1;
+            }
+        } else {
+            self.ring.push(byte);
+        }
+        if self.counts.add(byte) == 1 {
+            self.distinct += 1;
+        }
+
+        if self.distinct < self.size {
+            return false;
+        }
+        self.ring.clear();
+        self.head = 0;
+        self.counts = [0; 256];
+        self.distinct = 0;
+        true
+    }
+}
+
+/// Reads through to `R`, but drops the one line ending, `\n` or `\r\n`,
+/// that ends the stream, so a datastream saved with a final newline decodes
+/// like the bare one. Only the last two bytes are ever held back, until it is
+/// clear whether they end the stream.
+pub struct StripFinalNewline<R> {
+    reader: R,
+    buf: Vec<u8>,
+    start: usize,
+    eof: bool,
+}
+
+impl<R: Read> StripFinalNewline<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: vec![],
+            start: 0,
+            eof: false,
+        }
+    }
+}
+
+impl<R: Read> Read for StripFinalNewline<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        loop {
+            let held = match (self.eof, &self.buf[self.start..]) {
+                (true, _) => 0,
+                (false, [.., b'\r', b'\n']) => 2,
+                (false, [.., b'\r' | b'\n']) => 1,
+                (false, _) => 0,
+            };
+            let ready = self.buf.len() - self.start - held;
+            if ready > 0 || self.eof || out.is_empty() {
+                let len = ready.min(out.len());
+                out[..len].copy_from_slice(&self.buf[self.start..self.start + len]);
+                self.start += len;
+                return Ok(len);
+            }
+
+            self.buf.drain(..self.start);
+            self.start = 0;
+            let filled = self.buf.len();
+            self.buf.resize(filled + CHUNK_SIZE, 0);
+            let read = self.reader.read(&mut self.buf[filled..]);
+            self.buf
+                .truncate(filled + read.as_ref().map_or(0, |&len| len));
+            if read? == 0 {
+                self.eof = true;
+                let ending = match self.buf.as_slice() {
+                    [.., b'\r', b'\n'] => 2,
+                    [.., b'\n'] => 1,
+                    _ => 0,
+                };
+                self.buf.truncate(self.buf.len() - ending);
+            }
+        }
+    }
+}
+
+/// Reads a datastream chunk by chunk and yields every start-of-packet and
+/// start-of-message marker in it, in stream order. Memory use only depends
+/// on the marker sizes, not on the length of the stream.
+pub struct MarkerDecoder<R> {
+    reader: R,
+    chunk: Vec<u8>,
+    pos: usize,
+    len: usize,
+    offset: u64,
+    packet: MarkerWindow,
+    message: MarkerWindow,
+    pending: Option<MarkerEvent>,
+}
+
+impl<R: Read> MarkerDecoder<R> {
+    pub fn new(reader: R) -> Self {
+        Self::with_sizes(reader, SOP_SIZE, SOM_SIZE)
+    }
+
+    pub fn with_sizes(reader: R, packet_size: usize, message_size: usize) -> Self {
+        Self {
+            reader,
+            chunk: vec![0; CHUNK_SIZE],
+            pos: 0,
+            len: 0,
+            offset: 0,
+            packet: MarkerWindow::new(packet_size),
+            message: MarkerWindow::new(message_size),
+            pending: None,
+        }
+    }
+
+    /// Bytes consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl<R: Read> Iterator for MarkerDecoder<R> {
+    type Item = io::Result<MarkerEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(event) = self.pending.take() {
+            return Some(Ok(event));
+        }
+
+        loop {
+            if self.pos == self.len {
+                self.len = match self.reader.read(&mut self.chunk) {
+                    Ok(0) => return None,
+                    Ok(len) => len,
+                    Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                    Err(err) => return Some(Err(err)),
+                };
+                self.pos = 0;
+            }
+
+            let byte = self.chunk[self.pos];
+            self.pos += 1;
+            self.offset += 1;
+
+            let event = |kind| MarkerEvent {
+                kind,
+                offset: self.offset,
+            };
+            let packet = self
+                .packet
+                .push(byte)
+                .then(|| event(MarkerKind::StartOfPacket));
+            let message = self
+                .message
+                .push(byte)
+                .then(|| event(MarkerKind::StartOfMessage));
+            match (packet, message) {
+                (Some(packet), message) => {
+                    self.pending = message;
+                    return Some(Ok(packet));
+                }
+                (None, Some(message)) => return Some(Ok(message)),
+                (None, None) => {}
+            }
+        }
+    }
+}
+
+impl Solution for Day6 {
+    type Input = String;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(String::from(input))
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize> {
+        Ok(find_marker(input, SOP_SIZE, MarkerUnit::Chars)
+            .ok_or(MarkerError::NotFound(SOP_SIZE))?)
+    }
+
+    fn part2(input: &Self::Input) -> Result<usize> {
+        Ok(find_marker(input, SOM_SIZE, MarkerUnit::Chars)
+            .ok_or(MarkerError::NotFound(SOM_SIZE))?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::io::Read;
+
+    use proptest::prelude::*;
+
+    use super::{
+        find_marker, MarkerDecoder, MarkerEvent, MarkerKind, MarkerUnit, StripFinalNewline,
+        CHUNK_SIZE, SOM_SIZE, SOP_SIZE,
+    };
+
+    /// Hands out one byte per read, to cross a chunk boundary every byte.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl std::io::Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) if !buf.is_empty() => {
+                    buf[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn it_finds_the_example_markers() {
+        for (input, sop, som) in [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ] {
+            for unit in [MarkerUnit::Bytes, MarkerUnit::Chars] {
+                assert_eq!(find_marker(input, SOP_SIZE, unit), Some(sop));
+                assert_eq!(find_marker(input, SOM_SIZE, unit), Some(som));
+            }
+        }
+    }
+
+    #[test]
+    fn it_reports_missing_markers() {
+        assert_eq!(find_marker("abcabc", 4, MarkerUnit::Chars), None);
+        assert_eq!(find_marker("", 1, MarkerUnit::Bytes), None);
+    }
+
+    #[test]
+    fn it_counts_bytes_or_chars() {
+        // Each 'é' is two different bytes but one repeated char.
+        assert_eq!(find_marker("éé", 2, MarkerUnit::Bytes), Some(2));
+        assert_eq!(find_marker("éé", 2, MarkerUnit::Chars), None);
+        assert_eq!(find_marker("éaéb", 3, MarkerUnit::Chars), Some(4));
+    }
+
+    #[test]
+    fn it_streams_the_first_markers_like_the_puzzle() {
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+        let events: Vec<MarkerEvent> = MarkerDecoder::new(Trickle(input.as_bytes()))
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(
+            events[0],
+            MarkerEvent {
+                kind: MarkerKind::StartOfPacket,
+                offset: 7
+            }
+        );
+        let first_message = events
+            .iter()
+            .find(|event| event.kind == MarkerKind::StartOfMessage)
+            .unwrap();
+        assert_eq!(first_message.offset, 19);
+    }
+
+    #[test]
+    fn it_strips_one_final_newline() {
+        for (input, stripped) in [
+            ("abc\n", "abc"),
+            ("abc\r\n", "abc"),
+            ("ab\n\ncd\n\n", "ab\n\ncd\n"),
+            ("abc", "abc"),
+            ("ab\n\r", "ab\n\r"),
+        ] {
+            let mut read = String::new();
+            StripFinalNewline::new(Trickle(input.as_bytes()))
+                .read_to_string(&mut read)
+                .unwrap();
+            assert_eq!(read, stripped, "{input:?}");
+        }
+
+        let input = "mjqjpqmgbljsphdztnvjfqwrcgsmlb\n";
+        let last = MarkerDecoder::new(StripFinalNewline::new(input.as_bytes()))
+            .map(|event| event.unwrap().offset)
+            .max();
+        assert_eq!(last, Some(27));
+    }
+
+    #[test]
+    fn it_streams_runs_of_newlines_in_bounded_memory() {
+        let input = vec![b'\n'; 4 * CHUNK_SIZE];
+        let mut strip = StripFinalNewline::new(input.as_slice());
+        let (mut out, mut total, mut most) = ([0; 16], 0, 0);
+        loop {
+            let len = strip.read(&mut out).unwrap();
+            if len == 0 {
+                break;
+            }
+            assert!(out[..len].iter().all(|&byte| byte == b'\n'));
+            total += len;
+            most = most.max(strip.buf.capacity());
+        }
+
+        assert_eq!(total, input.len() - 1);
+        assert!(most <= 2 * CHUNK_SIZE, "{most}");
+    }
+
+    #[test]
+    fn it_streams_every_marker_without_overlap() {
+        let events: Vec<String> = MarkerDecoder::with_sizes("aabcdcdefgh".as_bytes(), 3, 5)
+            .map(|event| event.unwrap().to_string())
+            .collect();
+
+        assert_eq!(
+            events,
+            vec![
+                "start-of-packet at 4",
+                "start-of-packet at 8",
+                "start-of-message at 10",
+                "start-of-packet at 11"
+            ]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn it_agrees_with_checking_every_window(input in "[a-f]{0,40}", marker_size in 1usize..8) {
+            let chars: Vec<char> = input.chars().collect();
+            let expected = chars
+                .windows(marker_size)
+                .position(|window| window.iter().collect::<HashSet<_>>().len() == marker_size)
+                .map(|idx| idx + marker_size);
+
+            prop_assert_eq!(find_marker(&input, marker_size, MarkerUnit::Chars), expected);
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use anyhow::anyhow;
+
+pub use crate::elf_fs::{
+    process_efs, ElfFileSystem, BIG_DIRECTORY_SIZE, TOTAL_DISK_SIZE, UNUSED_SPACE_TARGET,
+};
+use crate::{Result, Solution};
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = ElfFileSystem;
+    type Part1 = u64;
+    type Part2 = u64;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        process_efs(input)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u64> {
+        Ok(input.get_part1_size())
+    }
+
+    fn part2(input: &Self::Input) -> Result<u64> {
+        input
+            .get_part2_size()
+            .ok_or_else(|| anyhow!("no directory is big enough to free up the space"))
+    }
+}
+
+/// The `HashMap` filesystem day 7 used before [`crate::elf_fs`], parser and
+/// all, kept as it was so the arena has something independent to be checked
+/// against.
+#[cfg(test)]
+mod legacy {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use anyhow::{anyhow, bail};
+    use itertools::Itertools;
+
+    use super::{BIG_DIRECTORY_SIZE, TOTAL_DISK_SIZE, UNUSED_SPACE_TARGET};
+    use crate::diagnostic::ParseError;
+    use crate::elf_fs::ElfTerminalError;
+    use crate::Result;
+
+    #[derive(Debug, PartialEq, Eq, Hash, Clone)]
+    struct ElfContentIndex {
+        full_path: PathBuf,
+    }
+
+    #[derive(Debug)]
+    struct ElfContent {
+        parent: Option<ElfContentIndex>,
+        kind: ElfContentKind,
+    }
+
+    #[derive(Debug)]
+    enum ElfContentKind {
+        File { size: u64 },
+        Dir { children: Vec<ElfContentIndex> },
+    }
+
+    impl ElfContent {
+        fn is_valid(&self) {
+            match &self.kind {
+                ElfContentKind::File { size: _ } => assert!(self.parent.is_some()),
+                ElfContentKind::Dir { children: _ } => (),
+            }
+        }
+    }
+
+    pub struct ElfFileSystem {
+        root: ElfContentIndex,
+        items: HashMap<ElfContentIndex, ElfContent>,
+    }
+
+    #[derive(Debug)]
+    enum ElfTerminalBlock {
+        CdCommandUp,
+        CdCommandRoot,
+        CdCommandDown {
+            dir_name: String,
+        },
+        LsCommand {
+            dir_listing: Vec<(ElfFileAttribute, String)>,
+        },
+    }
+
+    #[derive(Debug)]
+    enum ElfFileAttribute {
+        Dir,
+        Size(u64),
+    }
+
+    impl ElfFileSystem {
+        fn new() -> Self {
+            Self {
+                items: HashMap::new(),
+                root: ElfContentIndex {
+                    full_path: PathBuf::from("invalid"),
+                },
+            }
+        }
+
+        fn get_size_of_node(&self, index: &ElfContentIndex) -> Result<u64> {
+            let this_node = self
+                .items
+                .get(index)
+                .ok_or_else(|| anyhow!("did not get node"))?;
+            match &this_node.kind {
+                ElfContentKind::File { size } => Ok(*size),
+                ElfContentKind::Dir { children } => children
+                    .iter()
+                    .map(|child| self.get_size_of_node(child))
+                    .sum(),
+            }
+        }
+
+        fn get_total_size(&self) -> Result<u64> {
+            self.get_size_of_node(&self.root)
+        }
+
+        pub fn get_part1_size(&self) -> Result<u64> {
+            self.items
+                .iter()
+                .filter(|(_idx, content)| matches!(content.kind, ElfContentKind::Dir { .. }))
+                .map(|(idx, _content)| self.get_size_of_node(idx))
+                .map_ok(|size| if size < BIG_DIRECTORY_SIZE { size } else { 0 })
+                .sum()
+        }
+
+        pub fn get_part2_size(&self) -> Result<u64> {
+            let current_size = self.get_total_size()?;
+            let free_space = TOTAL_DISK_SIZE - current_size;
+            let target = UNUSED_SPACE_TARGET.saturating_sub(free_space);
+
+            self.items
+                .iter()
+                .filter(|(_idx, content)| matches!(content.kind, ElfContentKind::Dir { .. }))
+                .map(|(idx, _content)| self.get_size_of_node(idx))
+                .filter_ok(|&size| size > target)
+                .fold_ok(TOTAL_DISK_SIZE, u64::min)
+        }
+
+        fn process_command(
+            &mut self,
+            pos: &ElfContentIndex,
+            block: &ElfTerminalBlock,
+        ) -> Result<ElfContentIndex> {
+            match block {
+                ElfTerminalBlock::CdCommandRoot => {
+                    let new_root_idx = ElfContentIndex {
+                        full_path: PathBuf::from(r"efs-root"),
+                    };
+
+                    self.root = new_root_idx;
+                    self.items = HashMap::from([(
+                        self.root.clone(),
+                        ElfContent {
+                            parent: None,
+                            kind: ElfContentKind::Dir { children: vec![] },
+                        },
+                    )]);
+                    Ok(self.root.clone())
+                }
+                ElfTerminalBlock::CdCommandUp => {
+                    let node = self.items.get(pos).ok_or_else(|| anyhow!("No node"))?;
+                    match &node.parent {
+                        None => bail!("Can't CD past root"),
+                        Some(parent_pos) => Ok(parent_pos.clone()),
+                    }
+                }
+                ElfTerminalBlock::CdCommandDown { dir_name } => {
+                    self.items.get(pos).ok_or_else(|| anyhow!("No node"))?;
+                    let new_pos = ElfContentIndex {
+                        full_path: [
+                            pos.full_path
+                                .to_str()
+                                .ok_or(anyhow!("path to string fail"))?,
+                            dir_name.as_str(),
+                        ]
+                        .iter()
+                        .collect(),
+                    };
+                    assert!(self.items.contains_key(&new_pos));
+                    Ok(new_pos)
+                }
+                ElfTerminalBlock::LsCommand { dir_listing } => {
+                    for (attr, name) in dir_listing.iter() {
+                        let new_pos = ElfContentIndex {
+                            full_path: [
+                                pos.full_path
+                                    .to_str()
+                                    .ok_or(anyhow!("path to string fail"))?,
+                                name.as_str(),
+                            ]
+                            .iter()
+                            .collect(),
+                        };
+
+                        let parent_node = self
+                            .items
+                            .get_mut(pos)
+                            .ok_or_else(|| anyhow!("no parent!!!"))?;
+                        if let ElfContentKind::Dir { children } = &mut parent_node.kind {
+                            children.push(new_pos.clone());
+                        } else {
+                            bail!("parent is a file not a dir!!!");
+                        }
+
+                        let kind = match attr {
+                            ElfFileAttribute::Dir => ElfContentKind::Dir { children: vec![] },
+                            ElfFileAttribute::Size(size) => ElfContentKind::File { size: *size },
+                        };
+                        let content = ElfContent {
+                            parent: Some(pos.clone()),
+                            kind,
+                        };
+                        content.is_valid();
+
+                        self.items.insert(new_pos, content);
+                    }
+                    Ok(pos.clone())
+                }
+            }
+        }
+    }
+
+    fn parse_listing(input: &str, line: &str) -> Result<(ElfFileAttribute, String), ParseError> {
+        let (attr, name) = line
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(input, line, ElfTerminalError::MalformedListing))?;
+        let attr = match attr {
+            "dir" => ElfFileAttribute::Dir,
+            _ => ElfFileAttribute::Size(
+                attr.parse::<u64>()
+                    .map_err(|e| ParseError::new(input, attr, ElfTerminalError::from(e)))?,
+            ),
+        };
+        Ok((attr, String::from(name)))
+    }
+
+    /// Parses the text between two `$` prompts, a slice of the full `input`.
+    fn process_blocks(input: &str, block: &str) -> Result<ElfTerminalBlock, ParseError> {
+        let mut lines = block.lines();
+        let command = lines.next().map(str::trim).unwrap_or_default();
+        match command {
+            "ls" => Ok(ElfTerminalBlock::LsCommand {
+                dir_listing: lines
+                    .filter(|line| !line.trim().is_empty())
+                    .map(|line| parse_listing(input, line))
+                    .collect::<Result<_, _>>()?,
+            }),
+            "cd /" => Ok(ElfTerminalBlock::CdCommandRoot),
+            "cd .." => Ok(ElfTerminalBlock::CdCommandUp),
+            "" => Err(ParseError::new(
+                input,
+                block,
+                ElfTerminalError::EmptyCommand,
+            )),
+            _ => match command.strip_prefix("cd ") {
+                Some(dir_name) => Ok(ElfTerminalBlock::CdCommandDown {
+                    dir_name: String::from(dir_name),
+                }),
+                None => Err(ParseError::new(
+                    input,
+                    command,
+                    ElfTerminalError::UnknownCommand(String::from(command)),
+                )),
+            },
+        }
+    }
+
+    pub fn process_efs(terminal_output: &str) -> Result<ElfFileSystem> {
+        let mut efs = ElfFileSystem::new();
+        let mut track_pos = efs.root.clone();
+
+        let commands_iter = terminal_output
+            .split('$')
+            .filter(|&s| !s.is_empty())
+            .map(|block| process_blocks(terminal_output, block));
+
+        for command_res in commands_iter {
+            track_pos = efs.process_command(&track_pos, &command_res?)?;
+        }
+        Ok(efs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{legacy, process_efs};
+    use crate::cursed_day7::CursedDay7;
+    use crate::Solution;
+
+    /// A directory to turn into a transcript: file sizes and subdirectories.
+    #[derive(Debug, Clone)]
+    struct Dir(Vec<u64>, Vec<Dir>);
+
+    fn arb_dir() -> impl Strategy<Value = Dir> {
+        let files = prop::collection::vec(1u64..400_000, 0..4);
+        files
+            .clone()
+            .prop_map(|files| Dir(files, vec![]))
+            .prop_recursive(3, 40, 3, move |inner| {
+                (files.clone(), prop::collection::vec(inner, 0..3))
+                    .prop_map(|(files, dirs)| Dir(files, dirs))
+            })
+    }
+
+    /// Lists every directory depth first, the way the puzzle's elf did.
+    fn transcript(dir: &Dir, out: &mut String) {
+        out.push_str("$ ls\n");
+        for idx in 0..dir.1.len() {
+            out.push_str(&format!("dir d{idx}\n"));
+        }
+        for (idx, size) in dir.0.iter().enumerate() {
+            out.push_str(&format!("{size} f{idx}.txt\n"));
+        }
+        for (idx, child) in dir.1.iter().enumerate() {
+            out.push_str(&format!("$ cd d{idx}\n"));
+            transcript(child, out);
+            out.push_str("$ cd ..\n");
+        }
+    }
+
+    /// Checks the arena and the `Rc` tree against the old `HashMap`
+    /// filesystem, each reading the transcript with its own parser.
+    fn assert_all_agree(input: &str) {
+        let old = legacy::process_efs(input).unwrap();
+        let efs = process_efs(input).unwrap();
+        let cursed = CursedDay7::parse(input).unwrap();
+
+        let part1 = old.get_part1_size().unwrap();
+        assert_eq!(efs.get_part1_size(), part1);
+        assert_eq!(CursedDay7::part1(&cursed).unwrap(), part1);
+
+        let part2 = old.get_part2_size().unwrap();
+        assert_eq!(efs.get_part2_size().unwrap(), part2);
+        assert_eq!(CursedDay7::part2(&cursed).unwrap(), part2);
+    }
+
+    #[test]
+    fn it_agrees_with_the_old_filesystems_on_the_example() {
+        assert_all_agree(include_str!("../inputs/examples/day7.txt"));
+    }
+
+    proptest! {
+        #[test]
+        fn it_agrees_with_the_old_filesystems(root in arb_dir()) {
+            // Part 2 needs something on the disk to delete.
+            prop_assume!(!root.0.is_empty());
+            let mut input = String::from("$ cd /\n");
+            transcript(&root, &mut input);
+
+            assert_all_agree(&input);
+        }
+    }
+}
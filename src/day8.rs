@@ -0,0 +1,192 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution};
+
+pub struct Day8;
+
+#[derive(Debug, Error)]
+pub enum ElfForestError {
+    #[error("tree height {0:?} is not a digit")]
+    InvalidHeight(char),
+    #[error("row has {found} trees but the first row has {expected}")]
+    RaggedRow { expected: usize, found: usize },
+    #[error("the forest has no trees")]
+    EmptyForest,
+}
+
+#[derive(Debug)]
+pub struct ElfForest(Vec<Vec<u32>>, usize, usize);
+
+fn parse_row(input: &str, row: &str) -> Result<Vec<u32>, ParseError> {
+    row.char_indices()
+        .map(|(idx, c)| {
+            c.to_digit(10).ok_or_else(|| {
+                ParseError::new(
+                    input,
+                    &row[idx..idx + c.len_utf8()],
+                    ElfForestError::InvalidHeight(c),
+                )
+            })
+        })
+        .collect()
+}
+
+impl FromStr for ElfForest {
+    type Err = ParseError;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let trimmed = input.trim();
+        let rows: Vec<&str> = trimmed.lines().collect();
+        let first_row = rows
+            .first()
+            .filter(|row| !row.is_empty())
+            .ok_or_else(|| ParseError::new(input, trimmed, ElfForestError::EmptyForest))?;
+
+        let grid = rows
+            .iter()
+            .map(|row| {
+                let heights = parse_row(input, row)?;
+                if heights.len() != first_row.chars().count() {
+                    return Err(ParseError::new(
+                        input,
+                        row,
+                        ElfForestError::RaggedRow {
+                            expected: first_row.chars().count(),
+                            found: heights.len(),
+                        },
+                    ));
+                }
+                Ok(heights)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = grid.len() - 1;
+        let cols = grid[0].len() - 1;
+        Ok(Self(grid, rows, cols))
+    }
+}
+
+impl ElfForest {
+    pub fn heights(&self) -> &[Vec<u32>] {
+        &self.0
+    }
+}
+
+fn is_visible(ElfForest(grid, rows, cols): &ElfForest, row: usize, col: usize) -> bool {
+    let height = grid[row][col];
+    (0..col).all(|i| grid[row][i] < height)
+        || (0..row).all(|j| grid[j][col] < height)
+        || (col + 1..=*cols).all(|i| grid[row][i] < height)
+        || (row + 1..=*rows).all(|j| grid[j][col] < height)
+}
+
+/// Whether each tree can be seen from outside the grid. Trees on the edge
+/// always can.
+pub fn visibility_map(forest: &ElfForest) -> Vec<Vec<bool>> {
+    let ElfForest(_, rows, cols) = forest;
+    (0..=*rows)
+        .map(|row| {
+            (0..=*cols)
+                .map(|col| is_visible(forest, row, col))
+                .collect()
+        })
+        .collect()
+}
+
+fn visible_trees(forest: &ElfForest) -> u32 {
+    let ElfForest(_, rows, cols) = forest;
+    (1..*rows).fold((2 * (rows + cols)) as u32, |visible, row| {
+        (1..*cols).fold(visible, |visible, col| {
+            if is_visible(forest, row, col) {
+                visible + 1
+            } else {
+                visible
+            }
+        })
+    })
+}
+
+fn highest_scenic_score(ElfForest(grid, rows, cols): &ElfForest) -> u32 {
+    (1..*rows).fold(0u32, |score, row| {
+        (1..*cols).fold(score, |score, col| {
+            let height = grid[row][col];
+            let left = (0..col)
+                .enumerate()
+                .rev()
+                .find(|(_, c)| grid[row][*c] >= height)
+                .map(|(i, _)| col - i)
+                .unwrap_or(col);
+            let right = (col + 1..=*cols)
+                .enumerate()
+                .find(|(_, c)| grid[row][*c] >= height)
+                .map(|(i, _)| i + 1)
+                .unwrap_or(cols - col);
+            let up = (0..row)
+                .enumerate()
+                .rev()
+                .find(|(_, r)| grid[*r][col] >= height)
+                .map(|(i, _)| row - i)
+                .unwrap_or(row);
+            let down = (row + 1..=*rows)
+                .enumerate()
+                .find(|(_, r)| grid[*r][col] >= height)
+                .map(|(i, _)| i + 1)
+                .unwrap_or(rows - row);
+            score.max((left * up * right * down) as u32)
+        })
+    })
+}
+
+impl Solution for Day8 {
+    type Input = ElfForest;
+    type Part1 = u32;
+    type Part2 = u32;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input.parse()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<u32> {
+        Ok(visible_trees(input))
+    }
+
+    fn part2(input: &Self::Input) -> Result<u32> {
+        Ok(highest_scenic_score(input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Day8, ElfForestError};
+    use crate::diagnostic::ParseError;
+    use crate::Solution;
+
+    fn parse_error(input: &str) -> ParseError {
+        Day8::parse(input)
+            .unwrap_err()
+            .downcast::<ParseError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_rejects_malformed_forests() {
+        let err = parse_error("30373\n25x12\n");
+        assert!(matches!(
+            err.kind::<ElfForestError>(),
+            Some(ElfForestError::InvalidHeight('x'))
+        ));
+        assert_eq!((err.location.line, err.location.column), (2, 3));
+
+        let err = parse_error("303\n25\n");
+        assert!(matches!(
+            err.kind::<ElfForestError>(),
+            Some(ElfForestError::RaggedRow {
+                expected: 3,
+                found: 2
+            })
+        ));
+        assert_eq!((err.location.line, err.location.column), (2, 1));
+    }
+}
@@ -0,0 +1,307 @@
+use std::{collections::HashSet, str::FromStr};
+
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::{Result, Solution, Unsolved};
+
+pub struct Day9;
+
+#[derive(Debug, Error)]
+pub enum DirectionError {
+    #[error("expected a direction and a distance separated by a space")]
+    MissingDistance,
+    #[error("unknown direction {0:?}, expected U, D, L or R")]
+    UnknownDirection(String),
+    #[error("invalid distance: {0}")]
+    InvalidDistance(#[from] ParseIntError),
+    #[error("distance must be at least 1, found {0}")]
+    NonPositiveDistance(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Direction {
+    Up(i32),
+    Down(i32),
+    Left(i32),
+    Right(i32),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ElfRopePos {
+    pub x: i32,
+    pub y: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElfRopeState {
+    pub head: ElfRopePos,
+    pub tail: ElfRopePos,
+}
+
+struct ElfRopeStateStepper {
+    direction: Option<Direction>,
+    current_state: ElfRopeState,
+}
+
+impl Iterator for ElfRopeStateStepper {
+    type Item = ElfRopeState;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let remaining_direction = self.direction?;
+
+        let next_state = match remaining_direction {
+            Direction::Up(idx) => {
+                let next_x = self.current_state.head.x + 1;
+                let remaining_steps = idx - 1;
+                if remaining_steps == 0 {
+                    self.direction = None;
+                } else {
+                    self.direction = Some(Direction::Up(remaining_steps));
+                }
+                let next_head = ElfRopePos {
+                    x: next_x,
+                    y: self.current_state.head.y,
+                };
+
+                let mut next_tail = self.current_state.tail;
+                if next_tail.x < next_head.x - 1 {
+                    next_tail = ElfRopePos {
+                        x: next_head.x - 1,
+                        y: next_head.y,
+                    };
+                }
+                ElfRopeState {
+                    head: next_head,
+                    tail: next_tail,
+                }
+            }
+            Direction::Down(idx) => {
+                let next_x = self.current_state.head.x - 1;
+                let remaining_steps = idx - 1;
+                if remaining_steps == 0 {
+                    self.direction = None;
+                } else {
+                    self.direction = Some(Direction::Down(remaining_steps));
+                }
+                let next_head = ElfRopePos {
+                    x: next_x,
+                    y: self.current_state.head.y,
+                };
+
+                let mut next_tail = self.current_state.tail;
+                if next_tail.x > next_head.x + 1 {
+                    next_tail = ElfRopePos {
+                        x: next_head.x + 1,
+                        y: next_head.y,
+                    };
+                }
+                ElfRopeState {
+                    head: next_head,
+                    tail: next_tail,
+                }
+            }
+            Direction::Left(idx) => {
+                let next_y = self.current_state.head.y - 1;
+                let remaining_steps = idx - 1;
+                if remaining_steps == 0 {
+                    self.direction = None;
+                } else {
+                    self.direction = Some(Direction::Left(remaining_steps));
+                }
+                let next_head = ElfRopePos {
+                    x: self.current_state.head.x,
+                    y: next_y,
+                };
+
+                let mut next_tail = self.current_state.tail;
+                if next_tail.y > next_head.y + 1 {
+                    next_tail = ElfRopePos {
+                        x: next_head.x,
+                        y: next_head.y + 1,
+                    };
+                }
+                ElfRopeState {
+                    head: next_head,
+                    tail: next_tail,
+                }
+            }
+            Direction::Right(idx) => {
+                let next_y = self.current_state.head.y + 1;
+                let remaining_steps = idx - 1;
+                if remaining_steps == 0 {
+                    self.direction = None;
+                } else {
+                    self.direction = Some(Direction::Right(remaining_steps));
+                }
+                let next_head = ElfRopePos {
+                    x: self.current_state.head.x,
+                    y: next_y,
+                };
+
+                let mut next_tail = self.current_state.tail;
+                if next_tail.y < next_head.y - 1 {
+                    next_tail = ElfRopePos {
+                        x: next_head.x,
+                        y: next_head.y - 1,
+                    };
+                }
+                ElfRopeState {
+                    head: next_head,
+                    tail: next_tail,
+                }
+            }
+        };
+
+        self.current_state = next_state;
+        Some(next_state)
+    }
+}
+
+impl ElfRopeState {
+    fn rope_state_iter(&self, direction: Direction) -> ElfRopeStateStepper {
+        ElfRopeStateStepper {
+            direction: Some(direction),
+            current_state: *self,
+        }
+    }
+}
+
+fn parse_direction(input: &str, line: &str) -> Result<Direction, ParseError> {
+    let (direction, distance) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(input, line, DirectionError::MissingDistance))?;
+
+    let direction_vector: i32 = distance
+        .parse()
+        .map_err(|e| ParseError::new(input, distance, DirectionError::from(e)))?;
+    if direction_vector <= 0 {
+        return Err(ParseError::new(
+            input,
+            distance,
+            DirectionError::NonPositiveDistance(direction_vector),
+        ));
+    }
+
+    match direction {
+        "U" => Ok(Direction::Up(direction_vector)),
+        "D" => Ok(Direction::Down(direction_vector)),
+        "L" => Ok(Direction::Left(direction_vector)),
+        "R" => Ok(Direction::Right(direction_vector)),
+        _ => Err(ParseError::new(
+            input,
+            direction,
+            DirectionError::UnknownDirection(String::from(direction)),
+        )),
+    }
+}
+
+impl FromStr for Direction {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_direction(s, s)
+    }
+}
+
+/// Every state the rope passes through, starting with both knots at the
+/// origin.
+pub fn rope_states(directions: &[Direction]) -> Vec<ElfRopeState> {
+    let init_state = ElfRopeState {
+        tail: ElfRopePos { x: 0, y: 0 },
+        head: ElfRopePos { x: 0, y: 0 },
+    };
+
+    let mut states = vec![init_state];
+    let mut rope_state = init_state;
+
+    for direction in directions {
+        states.extend(rope_state.rope_state_iter(*direction));
+        rope_state = *states.last().unwrap_or(&rope_state);
+    }
+    states
+}
+
+fn count_tail_visits(directions: &[Direction]) -> usize {
+    let tail_pos_set: HashSet<ElfRopePos> = rope_states(directions)
+        .iter()
+        .map(|state| state.tail)
+        .collect();
+
+    tail_pos_set.len()
+}
+
+impl Solution for Day9 {
+    type Input = Vec<Direction>;
+    type Part1 = usize;
+    type Part2 = usize;
+
+    fn parse(input: &str) -> Result<Self::Input> {
+        Ok(input
+            .lines()
+            .map(|line| parse_direction(input, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part1(input: &Self::Input) -> Result<usize> {
+        Ok(count_tail_visits(input))
+    }
+
+    fn part2(_input: &Self::Input) -> Result<usize> {
+        Err(Unsolved.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lazy_static::lazy_static;
+
+    use super::{count_tail_visits, rope_states, Day9, Direction, ElfRopePos};
+    use crate::input::{InputResolver, InputSource};
+    use crate::Solution;
+
+    lazy_static! {
+        static ref EXAMPLE_FILE: String = InputResolver::from_env()
+            .and_then(|resolver| resolver.load(9, &InputSource::Variant(String::from("example"))))
+            .expect("Day 9 - Can't parse example file");
+        static ref TEST_EXAMPLE_DIRECTIONS: Vec<Direction> = vec![
+            Direction::Right(4),
+            Direction::Up(4),
+            Direction::Left(3),
+            Direction::Down(1),
+            Direction::Right(4),
+            Direction::Down(1),
+            Direction::Left(5),
+            Direction::Right(2),
+        ];
+    }
+
+    #[test]
+    fn it_parses_files_correctly() {
+        let parsed_direction = Day9::parse(&EXAMPLE_FILE);
+
+        assert!(parsed_direction.is_ok());
+        assert_eq!(parsed_direction.unwrap(), *TEST_EXAMPLE_DIRECTIONS);
+    }
+
+    #[test]
+    fn it_runs_part_1_for_example() {
+        let parsed_direction = Day9::parse(&EXAMPLE_FILE);
+
+        assert!(parsed_direction.is_ok());
+        assert_eq!(count_tail_visits(&parsed_direction.unwrap()), 13);
+    }
+
+    #[test]
+    fn it_keeps_moving_sideways() {
+        for (direction, sign) in [(Direction::Left(3), -1), (Direction::Right(3), 1)] {
+            let last = *rope_states(&[direction]).last().unwrap();
+
+            assert_eq!(last.head, ElfRopePos { x: 0, y: 3 * sign });
+            assert_eq!(last.tail, ElfRopePos { x: 0, y: 2 * sign });
+            assert_eq!(count_tail_visits(&[direction]), 3);
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use std::error::Error as StdError;
+use std::fmt;
+use std::path::PathBuf;
+
+use thiserror::Error;
+
+use crate::input::InputFile;
+
+/// Where in the input something went wrong. Lines and columns are 1-based
+/// and columns count characters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    pub file: Option<PathBuf>,
+    pub line: usize,
+    pub column: usize,
+    pub len: usize,
+    pub line_text: String,
+}
+
+impl Location {
+    /// Locates `fragment`, which must be a slice borrowed from `input`.
+    pub fn of(input: &str, fragment: &str) -> Self {
+        let input_start = input.as_ptr() as usize;
+        let fragment_start = fragment.as_ptr() as usize;
+        debug_assert!(
+            fragment_start >= input_start && fragment_start <= input_start + input.len(),
+            "Fragment is not part of the input"
+        );
+        let start = fragment_start.saturating_sub(input_start).min(input.len());
+
+        let line_start = input[..start].rfind('\n').map_or(0, |idx| idx + 1);
+        let line_end = input[start..]
+            .find('\n')
+            .map_or(input.len(), |idx| start + idx);
+        let line_text = input[line_start..line_end].trim_end_matches('\r');
+        let column = input[line_start..start].chars().count() + 1;
+        let remaining = line_text.chars().count().saturating_sub(column - 1);
+
+        Self {
+            file: None,
+            line: input[..start].matches('\n').count() + 1,
+            column,
+            len: fragment.chars().count().clamp(1, remaining.max(1)),
+            line_text: String::from(line_text),
+        }
+    }
+
+    /// Rewrites a location in a joined multi-file input so it points at the
+    /// file and line the text actually came from.
+    pub fn attribute(&mut self, files: &[InputFile]) {
+        if let Some(file) = files.iter().rev().find(|file| file.first_line <= self.line) {
+            self.line -= file.first_line - 1;
+            self.file = Some(file.path.clone());
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.file {
+            Some(file) => write!(f, "{}:{}:{}", file.display(), self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+/// A parse failure from any day: the day's own error enum plus where in the
+/// input it happened.
+#[derive(Debug, Error)]
+#[error("{kind} at {location}")]
+pub struct ParseError {
+    pub location: Location,
+    pub kind: Box<dyn StdError + Send + Sync + 'static>,
+}
+
+impl ParseError {
+    pub fn new(input: &str, fragment: &str, kind: impl StdError + Send + Sync + 'static) -> Self {
+        Self {
+            location: Location::of(input, fragment),
+            kind: Box::new(kind),
+        }
+    }
+
+    /// The day-specific error, if it is an `E`.
+    pub fn kind<E: StdError + 'static>(&self) -> Option<&E> {
+        self.kind.downcast_ref()
+    }
+
+    /// Renders the error with the offending line and a caret under the
+    /// offending text.
+    pub fn render(&self) -> String {
+        let gutter = " ".repeat(self.location.line.to_string().len());
+        format!(
+            "error: {}\n{}--> {}\n{} |\n{} | {}\n{} | {}{}",
+            self.kind,
+            gutter,
+            self.location,
+            gutter,
+            self.location.line,
+            self.location.line_text,
+            gutter,
+            " ".repeat(self.location.column - 1),
+            "^".repeat(self.location.len)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{Location, ParseError};
+    use crate::input::InputFile;
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("bad thing")]
+    struct BadThing;
+
+    #[test]
+    fn it_locates_fragments_by_line_and_column() {
+        let input = "first\nsecond line\nthird";
+        let location = Location::of(input, &input[13..17]);
+
+        assert_eq!(location.line, 2);
+        assert_eq!(location.column, 8);
+        assert_eq!(location.len, 4);
+        assert_eq!(location.line_text, "second line");
+    }
+
+    #[test]
+    fn it_attributes_joined_inputs_to_their_files() {
+        let input = "a\nb\n\nc\nd";
+        let mut location = Location::of(input, &input[7..8]);
+        location.attribute(&[
+            InputFile {
+                path: PathBuf::from("one.txt"),
+                first_line: 1,
+            },
+            InputFile {
+                path: PathBuf::from("two.txt"),
+                first_line: 4,
+            },
+        ]);
+
+        assert_eq!(location.to_string(), "two.txt:2:1");
+    }
+
+    #[test]
+    fn it_renders_a_caret_under_the_offending_text() {
+        let input = "A Y\nB Q\n";
+        let error = ParseError::new(input, &input[6..7], BadThing);
+
+        assert_eq!(
+            error.render(),
+            "error: bad thing\n --> line 2, column 3\n  |\n2 | B Q\n  |   ^"
+        );
+        assert!(error.kind::<BadThing>().is_some());
+    }
+}
@@ -0,0 +1,753 @@
+use std::collections::HashMap;
+use std::num::ParseIntError;
+
+use thiserror::Error;
+
+use crate::diagnostic::ParseError;
+use crate::elf_path::ElfPath;
+use crate::Result;
+
+pub const BIG_DIRECTORY_SIZE: u64 = 100000;
+pub const TOTAL_DISK_SIZE: u64 = 70000000;
+pub const UNUSED_SPACE_TARGET: u64 = 30000000;
+
+#[derive(Debug, Error)]
+pub enum ElfTerminalError {
+    #[error("expected a command after `$`")]
+    EmptyCommand,
+    #[error("unknown command {0:?}, expected `cd` or `ls`")]
+    UnknownCommand(String),
+    #[error("expected a listing like \"dir a\" or \"123 b.txt\"")]
+    MalformedListing,
+    #[error("listing before any `ls`")]
+    ListingOutsideLs,
+    #[error("invalid file size: {0}")]
+    InvalidSize(#[from] ParseIntError),
+}
+
+/// Something in a transcript that doesn't add up. Depending on the
+/// [`ReplayPolicy`] it stops the replay or is reported as a warning.
+/// Lines are 1-based.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ElfReplayError {
+    #[error("line {line}: can't cd past the root")]
+    CdPastRoot { line: usize },
+    #[error("line {line}: no directory {name:?} has been listed here")]
+    UnknownDir { line: usize, name: String },
+    #[error("line {line}: can't cd into {name:?}, it is a file")]
+    NotADir { line: usize, name: String },
+    #[error("line {line}: directory already listed on line {first}")]
+    RepeatedListing { line: usize, first: usize },
+    #[error("line {line}: {name:?} doesn't match how line {first} listed it")]
+    ConflictingEntry {
+        line: usize,
+        name: String,
+        first: usize,
+    },
+}
+
+impl ElfReplayError {
+    pub fn line(&self) -> usize {
+        match self {
+            ElfReplayError::CdPastRoot { line }
+            | ElfReplayError::UnknownDir { line, .. }
+            | ElfReplayError::NotADir { line, .. }
+            | ElfReplayError::RepeatedListing { line, .. }
+            | ElfReplayError::ConflictingEntry { line, .. } => *line,
+        }
+    }
+}
+
+/// What to do with a transcript that contradicts itself.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReplayPolicy {
+    /// Stop at the first inconsistency, including listing a directory twice.
+    #[default]
+    Strict,
+    /// Keep going and report each inconsistency as a warning: repeated
+    /// listings are merged, an entry that contradicts an earlier listing
+    /// keeps its first kind and size, `cd` into an unlisted directory
+    /// creates it and `cd ..` at the root stays there. `cd` into a file
+    /// still stops the replay.
+    Merge,
+}
+
+/// One line of an `ls` listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfListing {
+    pub line: usize,
+    pub name: String,
+    pub kind: ElfEntryKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElfEntryKind {
+    Dir,
+    File { size: u64 },
+}
+
+/// A command from the terminal transcript, with the line it was typed on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfCommand {
+    Cd {
+        line: usize,
+        dir: String,
+    },
+    Ls {
+        line: usize,
+        listing: Vec<ElfListing>,
+    },
+}
+
+impl ElfCommand {
+    pub fn line(&self) -> usize {
+        match self {
+            ElfCommand::Cd { line, .. } | ElfCommand::Ls { line, .. } => *line,
+        }
+    }
+}
+
+fn parse_listing(input: &str, line: &str, number: usize) -> Result<ElfListing, ParseError> {
+    let (attr, name) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(input, line, ElfTerminalError::MalformedListing))?;
+    let kind = match attr {
+        "dir" => ElfEntryKind::Dir,
+        _ => ElfEntryKind::File {
+            size: attr
+                .parse()
+                .map_err(|e| ParseError::new(input, attr, ElfTerminalError::from(e)))?,
+        },
+    };
+    Ok(ElfListing {
+        line: number,
+        name: String::from(name),
+        kind,
+    })
+}
+
+/// Parses a terminal transcript into commands, each `ls` holding the
+/// listing printed under it.
+pub fn parse_transcript(input: &str) -> Result<Vec<ElfCommand>, ParseError> {
+    let mut commands = vec![];
+    for (idx, line) in input.lines().enumerate() {
+        let number = idx + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Some(command) = line.strip_prefix('$') else {
+            match commands.last_mut() {
+                Some(ElfCommand::Ls { listing, .. }) => {
+                    listing.push(parse_listing(input, line, number)?)
+                }
+                _ => {
+                    return Err(ParseError::new(
+                        input,
+                        line,
+                        ElfTerminalError::ListingOutsideLs,
+                    ))
+                }
+            }
+            continue;
+        };
+
+        let command = command.trim();
+        match command {
+            "" => return Err(ParseError::new(input, line, ElfTerminalError::EmptyCommand)),
+            "ls" => commands.push(ElfCommand::Ls {
+                line: number,
+                listing: vec![],
+            }),
+            _ => match command.strip_prefix("cd ") {
+                Some(dir) => commands.push(ElfCommand::Cd {
+                    line: number,
+                    dir: String::from(dir),
+                }),
+                None => {
+                    return Err(ParseError::new(
+                        input,
+                        command,
+                        ElfTerminalError::UnknownCommand(String::from(command)),
+                    ))
+                }
+            },
+        }
+    }
+    Ok(commands)
+}
+
+/// Index of a node in an [`ElfFileSystem`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeId(usize);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ElfNodeKind {
+    File { size: u64 },
+    Dir { children: Vec<NodeId> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfNode {
+    /// The name in its parent's listing, empty for the root.
+    pub name: String,
+    pub parent: Option<NodeId>,
+    pub kind: ElfNodeKind,
+}
+
+/// The elves' filesystem as an arena of nodes that refer to each other by
+/// [`NodeId`]. The root directory is always the first node.
+///
+/// The total size of every node is cached alongside it and kept up to date
+/// as entries are added and removed, so asking for a size never walks the
+/// tree.
+#[derive(Debug, Clone)]
+pub struct ElfFileSystem {
+    nodes: Vec<ElfNode>,
+    sizes: Vec<u64>,
+}
+
+impl Default for ElfFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ElfFileSystem {
+    /// A filesystem holding only an empty root directory.
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![ElfNode {
+                name: String::new(),
+                parent: None,
+                kind: ElfNodeKind::Dir { children: vec![] },
+            }],
+            sizes: vec![0],
+        }
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn node(&self, id: NodeId) -> &ElfNode {
+        &self.nodes[id.0]
+    }
+
+    pub fn is_dir(&self, id: NodeId) -> bool {
+        matches!(self.node(id).kind, ElfNodeKind::Dir { .. })
+    }
+
+    /// The entries of a directory, in the order they were listed. Files have
+    /// none.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        match &self.node(id).kind {
+            ElfNodeKind::Dir { children } => children,
+            ElfNodeKind::File { .. } => &[],
+        }
+    }
+
+    pub fn child(&self, dir: NodeId, name: &str) -> Option<NodeId> {
+        self.children(dir)
+            .iter()
+            .copied()
+            .find(|&child| self.node(child).name == name)
+    }
+
+    /// The absolute path of a node, like `/a/e`.
+    pub fn path(&self, id: NodeId) -> ElfPath {
+        let mut names = vec![];
+        let mut current = id;
+        while let Some(parent) = self.node(current).parent {
+            names.push(self.node(current).name.as_str());
+            current = parent;
+        }
+
+        let mut path = ElfPath::root();
+        for name in names.into_iter().rev() {
+            path.push(name);
+        }
+        path
+    }
+
+    /// Follows `path` from the directory `from`. `..` at the root stays
+    /// there.
+    pub fn resolve(&self, from: NodeId, path: &ElfPath) -> Option<NodeId> {
+        let mut current = match path.is_absolute() {
+            true => self.root(),
+            false => from,
+        };
+        for _ in 0..path.up() {
+            current = self.node(current).parent.unwrap_or(current);
+        }
+        for name in path.names() {
+            current = self.child(current, name)?;
+        }
+        Some(current)
+    }
+
+    /// Whether `id` is `ancestor` or somewhere under it.
+    pub fn is_within(&self, id: NodeId, ancestor: NodeId) -> bool {
+        let mut current = Some(id);
+        while let Some(node) = current {
+            if node == ancestor {
+                return true;
+            }
+            current = self.node(node).parent;
+        }
+        false
+    }
+
+    /// Every node below `id` and then `id` itself, children before their
+    /// parent.
+    pub fn post_order(&self, id: NodeId) -> Vec<NodeId> {
+        let mut order = vec![];
+        let mut stack = vec![(id, false)];
+        while let Some((id, visited)) = stack.pop() {
+            if visited {
+                order.push(id);
+            } else {
+                stack.push((id, true));
+                stack.extend(self.children(id).iter().rev().map(|&child| (child, false)));
+            }
+        }
+        order
+    }
+
+    /// Adds an entry to the directory `parent`, adding a file's size to every
+    /// directory above it.
+    ///
+    /// # Panics
+    ///
+    /// If `parent` is a file.
+    pub fn add(&mut self, parent: NodeId, name: &str, kind: ElfEntryKind) -> NodeId {
+        let id = NodeId(self.nodes.len());
+        match &mut self.nodes[parent.0].kind {
+            ElfNodeKind::Dir { children } => children.push(id),
+            ElfNodeKind::File { .. } => panic!("{parent:?} is a file, not a directory"),
+        }
+        let (kind, size) = match kind {
+            ElfEntryKind::Dir => (ElfNodeKind::Dir { children: vec![] }, 0),
+            ElfEntryKind::File { size } => (ElfNodeKind::File { size }, size),
+        };
+        self.nodes.push(ElfNode {
+            name: String::from(name),
+            parent: Some(parent),
+            kind,
+        });
+        self.sizes.push(size);
+
+        // An empty directory or file changes no totals, so building a deep
+        // tree of directories doesn't walk up to the root for each one.
+        if size > 0 {
+            let mut ancestor = Some(parent);
+            while let Some(dir) = ancestor {
+                self.sizes[dir.0] += size;
+                ancestor = self.nodes[dir.0].parent;
+            }
+        }
+        id
+    }
+
+    /// Detaches `id` and everything under it from the tree, returning how
+    /// many bytes that frees, or `None` for the root or a node that is
+    /// already detached.
+    pub fn remove(&mut self, id: NodeId) -> Option<u64> {
+        let parent = self.nodes[id.0].parent.take()?;
+        if let ElfNodeKind::Dir { children } = &mut self.nodes[parent.0].kind {
+            children.retain(|&child| child != id);
+        }
+
+        let size = self.size(id);
+        let mut ancestor = Some(parent);
+        while let Some(dir) = ancestor {
+            self.sizes[dir.0] -= size;
+            ancestor = self.nodes[dir.0].parent;
+        }
+        Some(size)
+    }
+
+    /// The size of a file, or of everything under a directory.
+    pub fn size(&self, id: NodeId) -> u64 {
+        self.sizes[id.0]
+    }
+
+    /// Every node's size in one pass. Entries are always added after their
+    /// directory, so going backwards reaches children before their parent.
+    fn compute_sizes(&self) -> Vec<u64> {
+        let mut sizes = vec![0; self.nodes.len()];
+        for (idx, node) in self.nodes.iter().enumerate().rev() {
+            sizes[idx] = match &node.kind {
+                ElfNodeKind::File { size } => *size,
+                ElfNodeKind::Dir { children } => children.iter().map(|child| sizes[child.0]).sum(),
+            };
+        }
+        sizes
+    }
+
+    /// Space left on the disk, out of [`TOTAL_DISK_SIZE`].
+    pub fn free_space(&self) -> u64 {
+        TOTAL_DISK_SIZE.saturating_sub(self.total_size())
+    }
+
+    /// Rebuilds every cached size from the file sizes in one pass.
+    pub fn recompute_sizes(&mut self) {
+        self.sizes = self.compute_sizes();
+    }
+
+    /// Every directory under `id`, `id` included, with its total size.
+    /// Directories come before their parent, like `du` prints them.
+    pub fn du(&self, id: NodeId) -> Vec<(NodeId, u64)> {
+        self.post_order(id)
+            .into_iter()
+            .filter(|&dir| self.is_dir(dir))
+            .map(|dir| (dir, self.size(dir)))
+            .collect()
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.size(self.root())
+    }
+
+    /// The total size of every directory smaller than
+    /// [`BIG_DIRECTORY_SIZE`], counting nested directories again.
+    pub fn get_part1_size(&self) -> u64 {
+        self.du(self.root())
+            .into_iter()
+            .map(|(_, size)| size)
+            .filter(|&size| size < BIG_DIRECTORY_SIZE)
+            .sum()
+    }
+
+    /// The size of the smallest directory that frees up enough space for the
+    /// update when deleted.
+    pub fn get_part2_size(&self) -> Option<u64> {
+        let target = UNUSED_SPACE_TARGET.saturating_sub(self.free_space());
+
+        self.du(self.root())
+            .into_iter()
+            .map(|(_, size)| size)
+            .filter(|&size| size > target)
+            .min()
+    }
+}
+
+/// The state of a replay: where the terminal is and where everything was
+/// first seen.
+struct Replay {
+    efs: ElfFileSystem,
+    pos: NodeId,
+    policy: ReplayPolicy,
+    /// The line of the first `ls` of each directory.
+    listed: HashMap<NodeId, usize>,
+    /// The line each entry was first listed or `cd`-ed into on.
+    seen: HashMap<NodeId, usize>,
+    /// Every directory's entries by name, so finding one doesn't scan the
+    /// whole directory.
+    entries: HashMap<NodeId, HashMap<String, NodeId>>,
+    warnings: Vec<ElfReplayError>,
+}
+
+impl Replay {
+    /// The entry `name` in the current directory.
+    fn child(&self, name: &str) -> Option<NodeId> {
+        self.entries.get(&self.pos)?.get(name).copied()
+    }
+
+    /// Adds `name` to the current directory, first seen on `line`.
+    fn add(&mut self, line: usize, name: &str, kind: ElfEntryKind) -> NodeId {
+        let id = self.efs.add(self.pos, name, kind);
+        self.entries
+            .entry(self.pos)
+            .or_default()
+            .insert(String::from(name), id);
+        self.seen.insert(id, line);
+        id
+    }
+
+    fn report(&mut self, issue: ElfReplayError) -> Result<(), ElfReplayError> {
+        match self.policy {
+            ReplayPolicy::Strict => Err(issue),
+            ReplayPolicy::Merge => {
+                self.warnings.push(issue);
+                Ok(())
+            }
+        }
+    }
+
+    fn add_entry(&mut self, entry: &ElfListing) -> Result<(), ElfReplayError> {
+        let Some(existing) = self.child(&entry.name) else {
+            self.add(entry.line, &entry.name, entry.kind);
+            return Ok(());
+        };
+
+        let same = match (&self.efs.node(existing).kind, entry.kind) {
+            (ElfNodeKind::Dir { .. }, ElfEntryKind::Dir) => true,
+            (ElfNodeKind::File { size }, ElfEntryKind::File { size: listed }) => *size == listed,
+            _ => false,
+        };
+        if same {
+            return Ok(());
+        }
+        self.report(ElfReplayError::ConflictingEntry {
+            line: entry.line,
+            name: entry.name.clone(),
+            first: self.seen[&existing],
+        })
+    }
+
+    /// Moves the terminal into the directory `name` under it.
+    fn enter(&mut self, line: usize, name: &str) -> Result<(), ElfReplayError> {
+        match self.child(name) {
+            Some(child) if self.efs.is_dir(child) => self.pos = child,
+            Some(_) => {
+                return Err(ElfReplayError::NotADir {
+                    line,
+                    name: String::from(name),
+                })
+            }
+            None => {
+                self.report(ElfReplayError::UnknownDir {
+                    line,
+                    name: String::from(name),
+                })?;
+                self.pos = self.add(line, name, ElfEntryKind::Dir);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replays one command, moving the terminal to the directory it leaves
+    /// it in.
+    fn process_command(&mut self, command: &ElfCommand) -> Result<(), ElfReplayError> {
+        match command {
+            ElfCommand::Cd { line, dir } => {
+                let path = ElfPath::from(dir.as_str());
+                if path.is_absolute() {
+                    self.pos = self.efs.root();
+                }
+                for _ in 0..path.up() {
+                    match self.efs.node(self.pos).parent {
+                        Some(parent) => self.pos = parent,
+                        None => self.report(ElfReplayError::CdPastRoot { line: *line })?,
+                    }
+                }
+                for name in path.names() {
+                    self.enter(*line, name)?;
+                }
+            }
+            ElfCommand::Ls { line, listing } => {
+                if let Some(&first) = self.listed.get(&self.pos) {
+                    self.report(ElfReplayError::RepeatedListing { line: *line, first })?;
+                } else {
+                    self.listed.insert(self.pos, *line);
+                }
+                for entry in listing {
+                    self.add_entry(entry)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replays parsed commands into a filesystem, returning it along with any
+/// inconsistencies `policy` let through.
+pub fn replay(
+    commands: &[ElfCommand],
+    policy: ReplayPolicy,
+) -> Result<(ElfFileSystem, Vec<ElfReplayError>), ElfReplayError> {
+    let efs = ElfFileSystem::new();
+    let mut replay = Replay {
+        pos: efs.root(),
+        efs,
+        policy,
+        listed: HashMap::new(),
+        seen: HashMap::new(),
+        entries: HashMap::new(),
+        warnings: vec![],
+    };
+    for command in commands {
+        replay.process_command(command)?;
+    }
+    Ok((replay.efs, replay.warnings))
+}
+
+/// Parses a terminal transcript and replays it into a filesystem, stopping
+/// at the first inconsistency.
+pub fn process_efs(terminal_output: &str) -> Result<ElfFileSystem> {
+    let (efs, _) = replay(&parse_transcript(terminal_output)?, ReplayPolicy::Strict)?;
+    Ok(efs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse_transcript, process_efs, replay, ElfCommand, ElfEntryKind, ElfFileSystem,
+        ElfReplayError, ElfTerminalError, ReplayPolicy,
+    };
+    use crate::elf_path::ElfPath;
+
+    const EXAMPLE: &str = include_str!("../inputs/examples/day7.txt");
+
+    #[test]
+    fn it_solves_the_example() {
+        let efs = process_efs(EXAMPLE).unwrap();
+
+        assert_eq!(efs.total_size(), 48381165);
+        assert_eq!(efs.get_part1_size(), 95437);
+        assert_eq!(efs.get_part2_size(), Some(24933642));
+    }
+
+    #[test]
+    fn it_lists_directory_sizes_like_du() {
+        let efs = process_efs(EXAMPLE).unwrap();
+        let du: Vec<(&str, u64)> = efs
+            .du(efs.root())
+            .into_iter()
+            .map(|(dir, size)| (efs.node(dir).name.as_str(), size))
+            .collect();
+
+        assert_eq!(
+            du,
+            vec![("e", 584), ("a", 94853), ("d", 24933642), ("", 48381165)]
+        );
+    }
+
+    #[test]
+    fn it_keeps_sizes_up_to_date_as_entries_come_and_go() {
+        let mut efs = ElfFileSystem::new();
+        let a = efs.add(efs.root(), "a", ElfEntryKind::Dir);
+        let b = efs.add(a, "b", ElfEntryKind::Dir);
+        efs.add(b, "c", ElfEntryKind::File { size: 5 });
+        assert_eq!((efs.size(a), efs.total_size()), (5, 5));
+        efs.add(a, "d", ElfEntryKind::File { size: 7 });
+
+        assert_eq!((efs.size(b), efs.size(a), efs.total_size()), (5, 12, 12));
+        let cached = efs.du(efs.root());
+        efs.recompute_sizes();
+        assert_eq!(efs.du(efs.root()), cached);
+
+        assert_eq!(efs.path(b).to_string(), "/a/b");
+        assert_eq!(efs.remove(b), Some(5));
+        assert_eq!(efs.remove(b), None);
+        assert_eq!(efs.remove(efs.root()), None);
+        assert_eq!((efs.size(a), efs.total_size()), (7, 7));
+        assert_eq!(efs.child(a, "b"), None);
+    }
+
+    #[test]
+    fn it_parses_commands_with_their_lines() {
+        let commands = parse_transcript("$ cd /\n$ ls\ndir a\n14848514 b.txt\n$ cd a\n").unwrap();
+
+        assert_eq!(commands.len(), 3);
+        let ElfCommand::Ls { line, listing } = &commands[1] else {
+            panic!("expected an ls, got {:?}", commands[1]);
+        };
+        assert_eq!(*line, 2);
+        assert_eq!(listing[1].line, 4);
+        assert_eq!(listing[1].kind, ElfEntryKind::File { size: 14848514 });
+        assert_eq!(commands[2].line(), 5);
+    }
+
+    #[test]
+    fn it_rejects_listings_outside_ls() {
+        let error = parse_transcript("$ cd /\ndir a\n").unwrap_err();
+
+        assert!(matches!(
+            error.kind::<ElfTerminalError>(),
+            Some(ElfTerminalError::ListingOutsideLs)
+        ));
+        assert_eq!(error.location.line, 2);
+    }
+
+    #[test]
+    fn it_keeps_the_tree_when_going_back_to_root() {
+        let efs = process_efs("$ cd /\n$ ls\n10 a\ndir b\n$ cd b\n$ ls\n5 c\n$ cd /\n").unwrap();
+
+        assert_eq!(efs.total_size(), 15);
+    }
+
+    #[test]
+    fn it_reports_bad_cds_with_their_line() {
+        let error = process_efs("$ cd /\n$ ls\n10 a\n$ cd b\n").unwrap_err();
+
+        assert_eq!(
+            error.downcast_ref::<ElfReplayError>(),
+            Some(&ElfReplayError::UnknownDir {
+                line: 4,
+                name: String::from("b")
+            })
+        );
+    }
+
+    #[test]
+    fn it_merges_or_rejects_repeated_listings() {
+        let input = "$ cd /\n$ ls\n10 a\ndir b\n$ ls\n10 a\n20 b\n5 c\n";
+        let commands = parse_transcript(input).unwrap();
+
+        assert_eq!(
+            replay(&commands, ReplayPolicy::Strict).unwrap_err(),
+            ElfReplayError::RepeatedListing { line: 5, first: 2 }
+        );
+
+        let (efs, warnings) = replay(&commands, ReplayPolicy::Merge).unwrap();
+        assert_eq!(efs.total_size(), 15);
+        assert_eq!(
+            warnings,
+            vec![
+                ElfReplayError::RepeatedListing { line: 5, first: 2 },
+                ElfReplayError::ConflictingEntry {
+                    line: 7,
+                    name: String::from("b"),
+                    first: 4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_creates_unlisted_directories_when_merging() {
+        let commands = parse_transcript("$ cd ..\n$ cd a\n$ ls\n3 b\n$ cd b\n").unwrap();
+
+        let (efs, warnings) = replay(&commands[..3], ReplayPolicy::Merge).unwrap();
+        assert_eq!(efs.size(efs.child(efs.root(), "a").unwrap()), 3);
+        let lines: Vec<usize> = warnings.iter().map(ElfReplayError::line).collect();
+        assert_eq!(lines, vec![1, 2]);
+
+        assert_eq!(
+            replay(&commands, ReplayPolicy::Merge).unwrap_err(),
+            ElfReplayError::NotADir {
+                line: 5,
+                name: String::from("b")
+            }
+        );
+    }
+
+    #[test]
+    fn it_follows_multi_segment_cds() {
+        let input = "$ cd /\n$ ls\ndir a\n$ cd a\n$ ls\ndir my docs\n$ cd my docs\n$ ls\n4 x\n\
+                     $ cd ../../a/my docs/..\n$ ls\n3 y\n$ cd /a/my docs\n$ ls\n";
+        let commands = parse_transcript(input).unwrap();
+
+        let (efs, warnings) = replay(&commands, ReplayPolicy::Merge).unwrap();
+        assert_eq!(efs.total_size(), 7);
+        assert_eq!(
+            warnings,
+            vec![
+                ElfReplayError::RepeatedListing { line: 11, first: 5 },
+                ElfReplayError::RepeatedListing { line: 14, first: 8 },
+            ]
+        );
+
+        let docs = efs
+            .resolve(efs.root(), &ElfPath::from("a/./my docs"))
+            .unwrap();
+        assert_eq!(efs.path(docs).to_string(), "/a/my docs");
+        assert_eq!(
+            efs.resolve(docs, &ElfPath::from("../../..")),
+            Some(efs.root())
+        );
+        assert_eq!(efs.resolve(docs, &ElfPath::from("x/z")), None);
+    }
+}
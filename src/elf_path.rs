@@ -0,0 +1,127 @@
+use std::fmt;
+
+/// A normalised path in the elves' filesystem, like `/a/b` or `../x`.
+///
+/// `.` and empty segments are dropped and a `..` cancels the name before
+/// it. A `..` at the start of an absolute path stays at the root, so only
+/// relative paths keep leading `..`s, counted in `up`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ElfPath {
+    absolute: bool,
+    up: usize,
+    names: Vec<String>,
+}
+
+impl ElfPath {
+    /// The root directory, `/`.
+    pub fn root() -> Self {
+        Self {
+            absolute: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_absolute(&self) -> bool {
+        self.absolute
+    }
+
+    /// How many directories a relative path climbs before its names.
+    pub fn up(&self) -> usize {
+        self.up
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The last name in the path, if it has one.
+    pub fn file_name(&self) -> Option<&str> {
+        self.names.last().map(String::as_str)
+    }
+
+    /// Appends one segment, which may be `.` or `..`.
+    pub fn push(&mut self, segment: &str) {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                if self.names.pop().is_none() && !self.absolute {
+                    self.up += 1;
+                }
+            }
+            name => self.names.push(String::from(name)),
+        }
+    }
+
+    /// `other` resolved against this path, which is `other` itself when it
+    /// is absolute.
+    pub fn join(&self, other: &ElfPath) -> ElfPath {
+        if other.absolute {
+            return other.clone();
+        }
+        let mut joined = self.clone();
+        for _ in 0..other.up {
+            joined.push("..");
+        }
+        joined.names.extend(other.names.iter().cloned());
+        joined
+    }
+}
+
+impl From<&str> for ElfPath {
+    fn from(path: &str) -> Self {
+        let mut parsed = Self {
+            absolute: path.starts_with('/'),
+            ..Self::default()
+        };
+        for segment in path.split('/') {
+            parsed.push(segment);
+        }
+        parsed
+    }
+}
+
+impl fmt::Display for ElfPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let segments: Vec<&str> = std::iter::repeat_n("..", self.up)
+            .chain(self.names.iter().map(String::as_str))
+            .collect();
+        match (self.absolute, segments.is_empty()) {
+            (true, _) => write!(f, "/{}", segments.join("/")),
+            (false, true) => write!(f, "."),
+            (false, false) => write!(f, "{}", segments.join("/")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ElfPath;
+
+    #[test]
+    fn it_normalises_paths() {
+        for (path, normalised) in [
+            ("/", "/"),
+            ("/a//b/./c/..", "/a/b"),
+            ("/../a", "/a"),
+            ("../x", "../x"),
+            ("a/../../b", "../b"),
+            ("./", "."),
+            ("my dir/notes.txt", "my dir/notes.txt"),
+        ] {
+            assert_eq!(ElfPath::from(path).to_string(), normalised, "{path}");
+        }
+    }
+
+    #[test]
+    fn it_joins_relative_and_absolute_paths() {
+        let cwd = ElfPath::from("/a/b");
+
+        assert_eq!(cwd.join(&ElfPath::from("../x")).to_string(), "/a/x");
+        assert_eq!(cwd.join(&ElfPath::from("../../../..")), ElfPath::root());
+        assert_eq!(cwd.join(&ElfPath::from("/d")).to_string(), "/d");
+        assert_eq!(
+            ElfPath::from("..").join(&ElfPath::from("../c")).to_string(),
+            "../../c"
+        );
+    }
+}
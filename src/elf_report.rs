@@ -0,0 +1,219 @@
+use serde_json::json;
+
+use crate::elf_fs::{ElfFileSystem, ElfNodeKind, NodeId};
+
+/// How [`du_table`] orders its rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuOrder {
+    /// Smallest directory first, ties by path, like piping `du` to `sort -n`.
+    #[default]
+    Size,
+    /// Every directory after the ones inside it, like `du` itself.
+    PostOrder,
+}
+
+/// `bytes` in powers of 1024 with one decimal below 10, like `du -h`.
+pub fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["K", "M", "G", "T", "P", "E"];
+    if bytes < 1024 {
+        return format!("{}B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    match size < 10.0 {
+        true => format!("{:.1}{}", size, UNITS[unit]),
+        false => format!("{:.0}{}", size, UNITS[unit]),
+    }
+}
+
+/// `part` as a percentage of `whole`, or 0 when `whole` is empty.
+fn percent(part: u64, whole: u64) -> f64 {
+    match whole {
+        0 => 0.0,
+        _ => part as f64 * 100.0 / whole as f64,
+    }
+}
+
+/// Draws the tree under `dir`, one entry per line indented by depth, with
+/// its size and its share of the directory holding it. Directory names end
+/// in `/`.
+pub fn tree(efs: &ElfFileSystem, dir: NodeId) -> String {
+    let mut lines = vec![];
+    let mut stack = vec![(dir, 0)];
+    while let Some((id, depth)) = stack.pop() {
+        let node = efs.node(id);
+        let name = match (&node.kind, node.parent) {
+            (_, None) => String::from("/"),
+            (ElfNodeKind::Dir { .. }, Some(_)) => format!("{}/", node.name),
+            (ElfNodeKind::File { .. }, Some(_)) => node.name.clone(),
+        };
+        let share = match node.parent {
+            Some(parent) if id != dir => percent(efs.size(id), efs.size(parent)),
+            _ => 100.0,
+        };
+        lines.push(format!(
+            "{}{} {} ({:.1}%)",
+            "  ".repeat(depth),
+            name,
+            human_size(efs.size(id)),
+            share
+        ));
+        stack.extend(
+            efs.children(id)
+                .iter()
+                .rev()
+                .map(|&child| (child, depth + 1)),
+        );
+    }
+    lines.join("\n")
+}
+
+/// One row per directory under `dir`: its size in bytes, the same size for
+/// humans and its path.
+pub fn du_table(efs: &ElfFileSystem, dir: NodeId, order: DuOrder) -> String {
+    let mut rows = efs.du(dir);
+    if order == DuOrder::Size {
+        rows.sort_by_cached_key(|&(id, size)| (size, efs.path(id)));
+    }
+    rows.into_iter()
+        .map(|(id, size)| format!("{:>10} {:>6}  {}", size, human_size(size), efs.path(id)))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The filesystem in ncdu's JSON export format (version 1.2), so it can be
+/// browsed with `ncdu -f`. File sizes stand in for both the apparent size
+/// and the disk usage.
+///
+/// ncdu nests each directory as an array of its own info then its entries,
+/// with subdirectories as arrays and files as objects. The text is written
+/// directly, with an explicit stack, so deep trees don't need deep recursion.
+pub fn ncdu_export(efs: &ElfFileSystem, timestamp: u64) -> String {
+    let header = json!({
+        "progname": "aoc",
+        "progver": env!("CARGO_PKG_VERSION"),
+        "timestamp": timestamp,
+    });
+    let mut out = format!("[1,2,{},", header);
+
+    // Entries still to write, with `None` closing a directory.
+    let mut stack = vec![Some(efs.root())];
+    while let Some(entry) = stack.pop() {
+        let Some(id) = entry else {
+            out.push(']');
+            continue;
+        };
+        let node = efs.node(id);
+        if node.parent.is_some() {
+            out.push(',');
+        }
+        match node.kind {
+            ElfNodeKind::Dir { .. } => {
+                let name = match node.parent {
+                    None => "/",
+                    Some(_) => node.name.as_str(),
+                };
+                out.push_str(&format!("[{}", json!({ "name": name })));
+                stack.push(None);
+                stack.extend(efs.children(id).iter().rev().map(|&child| Some(child)));
+            }
+            ElfNodeKind::File { size } => out
+                .push_str(&json!({ "name": node.name, "asize": size, "dsize": size }).to_string()),
+        }
+    }
+    out.push(']');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{du_table, human_size, ncdu_export, tree, DuOrder};
+    use crate::elf_fs::{process_efs, ElfEntryKind, ElfFileSystem};
+
+    fn example() -> ElfFileSystem {
+        process_efs(include_str!("../inputs/examples/day7.txt")).unwrap()
+    }
+
+    #[test]
+    fn it_formats_human_sizes() {
+        for (bytes, human) in [
+            (0, "0B"),
+            (1023, "1023B"),
+            (1024, "1.0K"),
+            (94853, "93K"),
+            (24933642, "24M"),
+            (5 << 30, "5.0G"),
+        ] {
+            assert_eq!(human_size(bytes), human, "{bytes}");
+        }
+    }
+
+    #[test]
+    fn it_draws_the_tree_with_shares() {
+        let efs = example();
+        let a = efs.child(efs.root(), "a").unwrap();
+
+        assert_eq!(
+            tree(&efs, a),
+            "a/ 93K (100.0%)\n  e/ 584B (0.6%)\n    i 584B (100.0%)\n  f 28K (30.7%)\n  \
+             g 2.5K (2.7%)\n  h.lst 61K (66.0%)"
+        );
+        assert!(tree(&efs, efs.root()).starts_with("/ 46M (100.0%)\n  a/ 93K (0.2%)"));
+    }
+
+    #[test]
+    fn it_sorts_the_du_table() {
+        let efs = example();
+
+        assert_eq!(
+            du_table(&efs, efs.root(), DuOrder::Size),
+            "       584   584B  /a/e\n     94853    93K  /a\n  24933642    24M  /d\n  \
+             48381165    46M  /"
+        );
+        assert!(
+            du_table(&efs, efs.root(), DuOrder::PostOrder).ends_with("/d\n  48381165    46M  /")
+        );
+    }
+
+    #[test]
+    fn it_exports_ncdu_json() {
+        let efs = process_efs("$ cd /\n$ ls\ndir a\n12 b.txt\n$ cd a\n$ ls\n").unwrap();
+
+        let exported: Value = serde_json::from_str(&ncdu_export(&efs, 7)).unwrap();
+        assert_eq!(
+            exported,
+            json!([
+                1,
+                2,
+                { "progname": "aoc", "progver": env!("CARGO_PKG_VERSION"), "timestamp": 7 },
+                [
+                    { "name": "/" },
+                    [{ "name": "a" }],
+                    { "name": "b.txt", "asize": 12, "dsize": 12 },
+                ],
+            ])
+        );
+    }
+
+    #[test]
+    fn it_exports_deep_trees() {
+        let mut efs = ElfFileSystem::new();
+        let mut dir = efs.root();
+        for _ in 0..100_000 {
+            dir = efs.add(dir, "a", ElfEntryKind::Dir);
+        }
+        efs.add(dir, "f", ElfEntryKind::File { size: 1 });
+
+        let exported = ncdu_export(&efs, 0);
+        // The root, every directory under it and the export itself.
+        let closing = "]".repeat(100_000 + 2);
+        assert!(exported.ends_with(&format!(r#"{{"asize":1,"dsize":1,"name":"f"}}{closing}"#)));
+        assert_eq!(exported.matches('[').count(), 100_000 + 2);
+    }
+}
@@ -0,0 +1,294 @@
+use thiserror::Error;
+
+use crate::elf_fs::{ElfFileSystem, ElfNodeKind, NodeId, TOTAL_DISK_SIZE, UNUSED_SPACE_TARGET};
+use crate::elf_path::ElfPath;
+use crate::elf_report::tree;
+
+pub const HELP: &str = "\
+Paths can be absolute or relative, like /a/e or ../d; quote names with spaces.
+
+cd [DIR]             change directory, to / without DIR
+ls [DIR]             list a directory like the elves' terminal does
+pwd                  print the current directory
+du [DIR]             total size of every directory under DIR
+tree [DIR]           draw the tree under DIR with sizes and shares
+find [DIR] -size N   list entries bigger (+N), smaller (-N) or exactly N bytes
+rm PATH              remove a file or a directory and everything in it
+df                   disk usage and the space the update still needs
+exit                 leave the shell";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ShellError {
+    #[error("{0}: no such file or directory")]
+    NotFound(String),
+    #[error("{0}: not a directory")]
+    NotADir(String),
+    #[error("can't remove the root directory")]
+    RemoveRoot,
+    #[error("unknown command {0:?}, try `help`")]
+    UnknownCommand(String),
+    #[error("usage: {0}")]
+    Usage(&'static str),
+    #[error("missing closing quote")]
+    UnclosedQuote,
+}
+
+/// Splits a command line into words. Single or double quotes keep spaces
+/// in a word, and a backslash escapes the next character.
+fn split_words(line: &str) -> Result<Vec<String>, ShellError> {
+    let mut words = vec![];
+    let mut word: Option<String> = None;
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', _) => word.get_or_insert_with(String::new).extend(chars.next()),
+            (c, Some(open)) if c == open => quote = None,
+            (c, Some(_)) => word.get_or_insert_with(String::new).push(c),
+            ('"' | '\'', None) => {
+                quote = Some(c);
+                word.get_or_insert_with(String::new);
+            }
+            (c, None) if c.is_whitespace() => words.extend(word.take()),
+            (c, None) => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if quote.is_some() {
+        return Err(ShellError::UnclosedQuote);
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// A shell over a reconstructed filesystem, keeping track of the current
+/// directory.
+pub struct ElfShell {
+    efs: ElfFileSystem,
+    cwd: NodeId,
+}
+
+impl ElfShell {
+    pub fn new(efs: ElfFileSystem) -> Self {
+        let cwd = efs.root();
+        Self { efs, cwd }
+    }
+
+    pub fn filesystem(&self) -> &ElfFileSystem {
+        &self.efs
+    }
+
+    pub fn cwd(&self) -> NodeId {
+        self.cwd
+    }
+
+    /// The prompt to show before reading a command.
+    pub fn prompt(&self) -> String {
+        format!("elf:{}$ ", self.efs.path(self.cwd))
+    }
+
+    /// Finds `path`, absolute or relative to the current directory.
+    fn resolve(&self, path: &str) -> Result<NodeId, ShellError> {
+        self.efs
+            .resolve(self.cwd, &ElfPath::from(path))
+            .ok_or_else(|| ShellError::NotFound(String::from(path)))
+    }
+
+    /// Like [`ElfShell::resolve`], but `path` has to be a directory and
+    /// defaults to the current one.
+    fn resolve_dir(&self, path: Option<&str>) -> Result<NodeId, ShellError> {
+        let Some(path) = path else {
+            return Ok(self.cwd);
+        };
+        let id = self.resolve(path)?;
+        match self.efs.is_dir(id) {
+            true => Ok(id),
+            false => Err(ShellError::NotADir(String::from(path))),
+        }
+    }
+
+    /// Runs one command line and returns what it prints.
+    pub fn run(&mut self, line: &str) -> Result<String, ShellError> {
+        let words = split_words(line)?;
+        let words: Vec<&str> = words.iter().map(String::as_str).collect();
+        match words.as_slice() {
+            [] => Ok(String::new()),
+            ["help"] => Ok(String::from(HELP)),
+            ["cd"] => {
+                self.cwd = self.efs.root();
+                Ok(String::new())
+            }
+            ["cd", dir] => {
+                self.cwd = self.resolve_dir(Some(dir))?;
+                Ok(String::new())
+            }
+            ["pwd"] => Ok(self.efs.path(self.cwd).to_string()),
+            ["ls", rest @ ..] if rest.len() <= 1 => {
+                Ok(self.ls(self.resolve_dir(rest.first().copied())?))
+            }
+            ["du", rest @ ..] if rest.len() <= 1 => {
+                Ok(self.du(self.resolve_dir(rest.first().copied())?))
+            }
+            ["tree", rest @ ..] if rest.len() <= 1 => {
+                Ok(tree(&self.efs, self.resolve_dir(rest.first().copied())?))
+            }
+            ["find", "-size", size] => self.find(self.cwd, size),
+            ["find", dir, "-size", size] => self.find(self.resolve_dir(Some(dir))?, size),
+            ["find", ..] => Err(ShellError::Usage("find [DIR] -size [+-]N")),
+            ["rm", path] => self.rm(path),
+            ["rm", ..] => Err(ShellError::Usage("rm PATH")),
+            ["df"] => Ok(self.df()),
+            [command, ..] => Err(ShellError::UnknownCommand(String::from(*command))),
+        }
+    }
+
+    fn ls(&self, dir: NodeId) -> String {
+        self.efs
+            .children(dir)
+            .iter()
+            .map(|&child| {
+                let node = self.efs.node(child);
+                match node.kind {
+                    ElfNodeKind::Dir { .. } => format!("dir {}", node.name),
+                    ElfNodeKind::File { size } => format!("{} {}", size, node.name),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn du(&self, dir: NodeId) -> String {
+        self.efs
+            .du(dir)
+            .into_iter()
+            .map(|(dir, size)| format!("{:<10} {}", size, self.efs.path(dir)))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn find(&self, dir: NodeId, size: &str) -> Result<String, ShellError> {
+        let usage = ShellError::Usage("find [DIR] -size [+-]N");
+        let (compare, bound): (fn(&u64, &u64) -> bool, &str) =
+            match (size.strip_prefix('+'), size.strip_prefix('-')) {
+                (Some(bound), _) => (u64::gt, bound),
+                (_, Some(bound)) => (u64::lt, bound),
+                _ => (u64::eq, size),
+            };
+        let bound: u64 = bound.parse().map_err(|_| usage)?;
+
+        let mut found: Vec<NodeId> = self
+            .efs
+            .post_order(dir)
+            .into_iter()
+            .filter(|&id| compare(&self.efs.size(id), &bound))
+            .collect();
+        found.sort_by_key(|&id| self.efs.path(id));
+        Ok(found
+            .into_iter()
+            .map(|id| self.efs.path(id).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    fn rm(&mut self, path: &str) -> Result<String, ShellError> {
+        let id = self.resolve(path)?;
+        if self.efs.is_within(self.cwd, id) {
+            self.cwd = self.efs.node(id).parent.unwrap_or(self.cwd);
+        }
+        self.efs.remove(id).ok_or(ShellError::RemoveRoot)?;
+        Ok(String::new())
+    }
+
+    fn df(&self) -> String {
+        let used = self.efs.total_size();
+        let free = self.efs.free_space();
+        let needed = UNUSED_SPACE_TARGET.saturating_sub(free);
+        format!(
+            "{:>10} {:>10} {:>10} {:>10}\n{:>10} {:>10} {:>10} {:>10}",
+            "total", "used", "free", "needed", TOTAL_DISK_SIZE, used, free, needed
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ElfShell, ShellError};
+    use crate::elf_fs::process_efs;
+
+    fn shell() -> ElfShell {
+        ElfShell::new(process_efs(include_str!("../inputs/examples/day7.txt")).unwrap())
+    }
+
+    #[test]
+    fn it_moves_around_and_lists_directories() {
+        let mut shell = shell();
+
+        shell.run("cd a").unwrap();
+        shell.run("cd e").unwrap();
+        assert_eq!(shell.run("pwd").unwrap(), "/a/e");
+        assert_eq!(
+            shell.run("ls ..").unwrap(),
+            "dir e\n29116 f\n2557 g\n62596 h.lst"
+        );
+        assert_eq!(
+            shell.run("cd i").unwrap_err(),
+            ShellError::NotADir(String::from("i"))
+        );
+        shell.run("cd").unwrap();
+        assert_eq!(shell.prompt(), "elf:/$ ");
+    }
+
+    #[test]
+    fn it_finds_directories_by_size() {
+        let mut shell = shell();
+
+        assert_eq!(shell.run("find -size +20000000").unwrap(), "/\n/d");
+        assert_eq!(shell.run("find a -size -1000").unwrap(), "/a/e\n/a/e/i");
+        assert_eq!(shell.run("du a").unwrap(), "584        /a/e\n94853      /a");
+    }
+
+    #[test]
+    fn it_frees_space_when_removing() {
+        let mut shell = shell();
+        shell.run("cd d").unwrap();
+
+        shell.run("rm .").unwrap();
+        assert_eq!(shell.run("pwd").unwrap(), "/");
+        assert_eq!(
+            shell.run("df").unwrap(),
+            "     total       used       free     needed\n  70000000   23447523   46552477          0"
+        );
+        assert_eq!(shell.run("rm /").unwrap_err(), ShellError::RemoveRoot);
+    }
+
+    #[test]
+    fn it_follows_paths_with_spaces() {
+        let mut shell = shell();
+        shell.run("rm /a/e/i").unwrap();
+        shell.run("cd a/e").unwrap();
+
+        assert_eq!(shell.run("ls").unwrap(), "");
+        assert_eq!(shell.run(r#"cd "../../d" "#).unwrap(), "");
+        assert_eq!(shell.run("pwd").unwrap(), "/d");
+        assert_eq!(
+            shell.run(r"cd ../my\ docs").unwrap_err(),
+            ShellError::NotFound(String::from("../my docs"))
+        );
+        assert_eq!(shell.run("cd 'a").unwrap_err(), ShellError::UnclosedQuote);
+    }
+
+    #[test]
+    fn it_enters_directories_with_spaces() {
+        let efs = process_efs("$ cd /\n$ ls\ndir my docs\n$ cd my docs\n$ ls\n5 notes.txt\n");
+        let mut shell = ElfShell::new(efs.unwrap());
+
+        shell.run(r#"cd "my docs""#).unwrap();
+        assert_eq!(shell.run("pwd").unwrap(), "/my docs");
+        shell.run(r"cd /my\ docs/..").unwrap();
+        shell.run("cd 'my docs'").unwrap();
+        assert_eq!(shell.run("ls").unwrap(), "5 notes.txt");
+        assert_eq!(
+            shell.run("tree /").unwrap(),
+            "/ 5B (100.0%)\n  my docs/ 5B (100.0%)\n    notes.txt 5B (100.0%)"
+        );
+    }
+}
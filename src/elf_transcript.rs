@@ -0,0 +1,325 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+
+use crate::compact::SplitMix64;
+use crate::elf_fs::{ElfEntryKind, ElfFileSystem, ElfNodeKind, NodeId};
+use crate::Result;
+
+/// The order the elf explores directories in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TraversalOrder {
+    /// Each directory's subdirectories right after it, like the puzzle.
+    #[default]
+    DepthFirst,
+    /// Every directory at one depth before any deeper one.
+    BreadthFirst,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TranscriptConfig {
+    pub order: TraversalOrder,
+    /// Sprinkle in commands that don't change the tree: a second `ls` of
+    /// the same directory, or a `cd ..` or `cd /` followed by the way back.
+    /// The second `ls` only replays with [`ReplayPolicy::Merge`].
+    ///
+    /// [`ReplayPolicy::Merge`]: crate::elf_fs::ReplayPolicy::Merge
+    pub noise: bool,
+    /// Seed for where the noise goes.
+    pub seed: u64,
+}
+
+/// Builds the filesystem under `root` on disk. Entries are added in name
+/// order, symlinks and other special files are skipped and sizes are file
+/// lengths in bytes. Names that aren't UTF-8 or can't be written on one
+/// transcript line are an error.
+pub fn read_dir_tree(root: &Path) -> Result<ElfFileSystem> {
+    let mut efs = ElfFileSystem::new();
+    let mut pending = vec![(efs.root(), root.to_path_buf())];
+
+    while let Some((dir, path)) = pending.pop() {
+        let mut entries = fs::read_dir(&path)
+            .with_context(|| format!("Could not read {}", path.display()))?
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("Could not read {}", path.display()))?;
+        entries.sort_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let Ok(name) = entry.file_name().into_string() else {
+                bail!("{} is not valid UTF-8", entry.path().display());
+            };
+            if name.contains('\n') || name.trim() != name {
+                bail!(
+                    "{} can't be written in a transcript",
+                    entry.path().display()
+                );
+            }
+            let file_type = entry
+                .file_type()
+                .with_context(|| format!("Could not read {}", entry.path().display()))?;
+
+            if file_type.is_dir() {
+                let child = efs.add(dir, &name, ElfEntryKind::Dir);
+                pending.push((child, entry.path()));
+            } else if file_type.is_file() {
+                let size = entry
+                    .metadata()
+                    .with_context(|| format!("Could not read {}", entry.path().display()))?
+                    .len();
+                efs.add(dir, &name, ElfEntryKind::File { size });
+            }
+        }
+    }
+    Ok(efs)
+}
+
+/// Every directory in the order `order` visits them, root first.
+fn dir_order(efs: &ElfFileSystem, order: TraversalOrder) -> Vec<NodeId> {
+    let is_dir = |&&child: &&NodeId| efs.is_dir(child);
+    let mut dirs = vec![];
+    match order {
+        TraversalOrder::DepthFirst => {
+            let mut stack = vec![efs.root()];
+            while let Some(dir) = stack.pop() {
+                dirs.push(dir);
+                stack.extend(efs.children(dir).iter().rev().filter(is_dir));
+            }
+        }
+        TraversalOrder::BreadthFirst => {
+            let mut queue = VecDeque::from([efs.root()]);
+            while let Some(dir) = queue.pop_front() {
+                dirs.push(dir);
+                queue.extend(efs.children(dir).iter().filter(is_dir));
+            }
+        }
+    }
+    dirs
+}
+
+/// The `cd`s that take the terminal from `from` to `to`: up to their
+/// closest common directory, then down one name at a time.
+fn navigate(efs: &ElfFileSystem, from: NodeId, to: NodeId, out: &mut String) {
+    let mut current = from;
+    while !efs.is_within(to, current) {
+        out.push_str("$ cd ..\n");
+        current = efs.node(current).parent.unwrap_or(current);
+    }
+
+    let mut down = vec![];
+    let mut dir = to;
+    while dir != current {
+        down.push(dir);
+        dir = efs.node(dir).parent.unwrap_or(current);
+    }
+    for dir in down.into_iter().rev() {
+        out.push_str(&format!("$ cd {}\n", efs.node(dir).name));
+    }
+}
+
+fn listing(efs: &ElfFileSystem, dir: NodeId, out: &mut String) {
+    out.push_str("$ ls\n");
+    for &child in efs.children(dir) {
+        let node = efs.node(child);
+        match node.kind {
+            ElfNodeKind::Dir { .. } => out.push_str(&format!("dir {}\n", node.name)),
+            ElfNodeKind::File { size } => out.push_str(&format!("{} {}\n", size, node.name)),
+        }
+    }
+}
+
+/// Writes the terminal session of an elf listing every directory of `efs`,
+/// in the puzzle's format.
+pub fn to_transcript(efs: &ElfFileSystem, config: &TranscriptConfig) -> String {
+    let mut rng = SplitMix64(config.seed);
+    let mut out = String::from("$ cd /\n");
+    let mut current = efs.root();
+
+    for dir in dir_order(efs, config.order) {
+        navigate(efs, current, dir, &mut out);
+        current = dir;
+        listing(efs, dir, &mut out);
+
+        if config.noise && rng.below(3) == 0 {
+            listing(efs, dir, &mut out);
+        }
+        if config.noise && dir != efs.root() && rng.below(3) == 0 {
+            out.push_str("$ cd ..\n");
+            out.push_str(&format!("$ cd {}\n", efs.node(dir).name));
+        }
+        if config.noise && rng.below(3) == 0 {
+            out.push_str("$ cd /\n");
+            navigate(efs, efs.root(), dir, &mut out);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+
+    use proptest::prelude::*;
+
+    use super::{read_dir_tree, to_transcript, TranscriptConfig, TraversalOrder};
+    use crate::elf_fs::{
+        parse_transcript, process_efs, replay, ElfFileSystem, ElfReplayError, ReplayPolicy,
+    };
+
+    /// Files with their sizes and subdirectories, by name.
+    #[derive(Debug, Clone)]
+    struct Dir(BTreeMap<String, u64>, BTreeMap<String, Dir>);
+
+    /// Names, some with a space in them.
+    const NAME: &str = "[a-z]{1,6}( [a-z.]{1,4})?";
+
+    fn arb_dir() -> impl Strategy<Value = Dir> {
+        let files = prop::collection::btree_map(NAME, 0u64..2_000, 0..4);
+        files
+            .clone()
+            .prop_map(|files| Dir(files, BTreeMap::new()))
+            .prop_recursive(3, 30, 3, move |inner| {
+                (
+                    files.clone(),
+                    prop::collection::btree_map(NAME, inner, 0..3),
+                )
+                    .prop_map(|(files, mut dirs)| {
+                        dirs.retain(|name, _| !files.contains_key(name));
+                        Dir(files, dirs)
+                    })
+            })
+    }
+
+    fn write_dir(dir: &Dir, path: &Path) {
+        for (name, &size) in &dir.0 {
+            fs::write(path.join(name), vec![b'x'; size as usize]).unwrap();
+        }
+        for (name, child) in &dir.1 {
+            fs::create_dir(path.join(name)).unwrap();
+            write_dir(child, &path.join(name));
+        }
+    }
+
+    /// Every directory's path and size.
+    fn sizes(efs: &ElfFileSystem) -> BTreeMap<String, u64> {
+        efs.du(efs.root())
+            .into_iter()
+            .map(|(dir, size)| (efs.path(dir).to_string(), size))
+            .collect()
+    }
+
+    #[test]
+    fn it_reads_a_directory_from_disk() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::create_dir(tmp.path().join("a")).unwrap();
+        fs::write(tmp.path().join("a").join("f"), "12345").unwrap();
+        fs::write(tmp.path().join("b.txt"), "123").unwrap();
+
+        let efs = read_dir_tree(tmp.path()).unwrap();
+        assert_eq!(
+            to_transcript(&efs, &TranscriptConfig::default()),
+            "$ cd /\n$ ls\ndir a\n3 b.txt\n$ cd a\n$ ls\n5 f\n"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_refuses_names_that_are_not_utf8() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let name = OsStr::from_bytes(b"bad\xff");
+        // Some filesystems only take UTF-8 names.
+        if fs::write(tmp.path().join(name), "1").is_err() {
+            return;
+        }
+
+        let err = read_dir_tree(tmp.path()).unwrap_err();
+        assert!(err.to_string().ends_with("is not valid UTF-8"), "{err}");
+    }
+
+    #[test]
+    fn it_lists_breadth_first() {
+        let efs = process_efs("$ cd /\n$ ls\ndir a\ndir b\n$ cd a\n$ ls\ndir c\n").unwrap();
+        let config = TranscriptConfig {
+            order: TraversalOrder::BreadthFirst,
+            ..TranscriptConfig::default()
+        };
+
+        assert_eq!(
+            to_transcript(&efs, &config),
+            "$ cd /\n$ ls\ndir a\ndir b\n$ cd a\n$ ls\ndir c\n$ cd ..\n$ cd b\n$ ls\n\
+             $ cd ..\n$ cd a\n$ cd c\n$ ls\n"
+        );
+    }
+
+    #[test]
+    fn it_adds_repeated_listings_as_noise() {
+        let efs = process_efs(include_str!("../inputs/examples/day7.txt")).unwrap();
+        let repeated = (0..16)
+            .map(|seed| {
+                let config = TranscriptConfig {
+                    noise: true,
+                    seed,
+                    ..TranscriptConfig::default()
+                };
+                parse_transcript(&to_transcript(&efs, &config)).unwrap()
+            })
+            .find(|commands| replay(commands, ReplayPolicy::Strict).is_err())
+            .expect("some seed repeats a listing");
+
+        let (merged, warnings) = replay(&repeated, ReplayPolicy::Merge).unwrap();
+        assert!(matches!(
+            warnings[..],
+            [ElfReplayError::RepeatedListing { .. }, ..]
+        ));
+        assert_eq!(sizes(&merged), sizes(&efs));
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn it_round_trips_through_a_transcript(
+            root in arb_dir(),
+            breadth_first in any::<bool>(),
+            noise in any::<bool>(),
+            seed in any::<u64>(),
+        ) {
+            let tmp = tempfile::tempdir().unwrap();
+            write_dir(&root, tmp.path());
+            let efs = read_dir_tree(tmp.path()).unwrap();
+
+            let config = TranscriptConfig {
+                order: match breadth_first {
+                    true => TraversalOrder::BreadthFirst,
+                    false => TraversalOrder::DepthFirst,
+                },
+                noise,
+                seed,
+            };
+            let transcript = to_transcript(&efs, &config);
+            let replayed = match noise {
+                // A second `ls` of a directory only passes when merging, and
+                // is the only thing merging has to smooth over.
+                true => {
+                    let (replayed, warnings) =
+                        replay(&parse_transcript(&transcript).unwrap(), ReplayPolicy::Merge)
+                            .unwrap();
+                    let repeats_only = warnings
+                        .iter()
+                        .all(|w| matches!(w, ElfReplayError::RepeatedListing { .. }));
+                    prop_assert!(repeats_only, "{:?}", warnings);
+                    replayed
+                }
+                false => process_efs(&transcript).unwrap(),
+            };
+
+            prop_assert_eq!(sizes(&replayed), sizes(&efs));
+        }
+    }
+}
@@ -0,0 +1,196 @@
+use thiserror::Error;
+
+use crate::day6::{find_byte_marker, SOM_SIZE};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FrameError {
+    #[error("marker size must be between 1 and 256, found {0}")]
+    InvalidMarkerSize(usize),
+    #[error(
+        "payload {frame} contains a run of {marker_size} different bytes, which reads as a marker"
+    )]
+    PayloadContainsMarker { frame: usize, marker_size: usize },
+}
+
+/// One message in a datastream: a marker followed by everything up to the
+/// next marker.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame<'a> {
+    /// Byte offset of the start of the marker.
+    pub offset: usize,
+    pub marker: &'a [u8],
+    pub payload: &'a [u8],
+}
+
+impl Frame<'_> {
+    /// Byte offset of the start of the payload.
+    pub fn payload_offset(&self) -> usize {
+        self.offset + self.marker.len()
+    }
+}
+
+/// Splits a datastream into [`Frame`]s. Anything before the first marker is
+/// the preamble.
+pub struct Frames<'a> {
+    data: &'a [u8],
+    marker_size: usize,
+    next_marker: Option<usize>,
+}
+
+impl<'a> Frames<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self::with_marker_size(data, SOM_SIZE)
+    }
+
+    pub fn with_marker_size(data: &'a [u8], marker_size: usize) -> Self {
+        let next_marker = find_byte_marker(data, marker_size).map(|end| end - marker_size);
+        Self {
+            data,
+            marker_size,
+            next_marker,
+        }
+    }
+
+    /// The bytes before the first marker, or the whole stream if there is
+    /// none.
+    pub fn preamble(&self) -> &'a [u8] {
+        let first = find_byte_marker(self.data, self.marker_size)
+            .map_or(self.data.len(), |end| end - self.marker_size);
+        &self.data[..first]
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = Frame<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.next_marker?;
+        let payload_start = offset + self.marker_size;
+        let rest = &self.data[payload_start..];
+
+        self.next_marker = find_byte_marker(rest, self.marker_size)
+            .map(|end| payload_start + end - self.marker_size);
+        let payload_end = self.next_marker.unwrap_or(self.data.len());
+
+        Some(Frame {
+            offset,
+            marker: &self.data[offset..payload_start],
+            payload: &self.data[payload_start..payload_end],
+        })
+    }
+}
+
+/// Bytes to build markers from, most readable first.
+fn marker_alphabet() -> impl Iterator<Item = u8> {
+    (b'a'..=b'z').chain(b'A'..=b'Z').chain(0..=u8::MAX)
+}
+
+/// Writes each payload behind a marker of `marker_size` different bytes,
+/// so that [`Frames`] reads the payloads back exactly.
+///
+/// Each marker starts with the byte written just before it. No window that
+/// ends inside the marker can then be all different, so the decoder can't
+/// find the marker early by borrowing bytes from the end of the payload.
+pub fn encode_frames(payloads: &[&[u8]], marker_size: usize) -> Result<Vec<u8>, FrameError> {
+    if !(1..=256).contains(&marker_size) {
+        return Err(FrameError::InvalidMarkerSize(marker_size));
+    }
+
+    let mut stream = vec![];
+    for (frame, payload) in payloads.iter().enumerate() {
+        if find_byte_marker(payload, marker_size).is_some() {
+            return Err(FrameError::PayloadContainsMarker { frame, marker_size });
+        }
+
+        let first = stream.last().copied().unwrap_or(b'a');
+        let mut marker = vec![first];
+        for byte in marker_alphabet() {
+            if marker.len() == marker_size {
+                break;
+            }
+            if !marker.contains(&byte) {
+                marker.push(byte);
+            }
+        }
+
+        stream.extend_from_slice(&marker);
+        stream.extend_from_slice(payload);
+    }
+    Ok(stream)
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::{encode_frames, FrameError, Frames};
+
+    #[test]
+    fn it_splits_a_stream_into_frames() {
+        let frames = Frames::with_marker_size(b"aaabcdabbaaxyzyy", 4);
+
+        assert_eq!(frames.preamble(), b"aa");
+        let frames: Vec<_> = frames.collect();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            (frames[0].offset, frames[0].marker, frames[0].payload),
+            (2, &b"abcd"[..], &b"abba"[..])
+        );
+        assert_eq!(frames[1].marker, b"axyz");
+        assert_eq!(frames[1].payload_offset(), 14);
+        assert_eq!(frames[1].payload, b"yy");
+    }
+
+    #[test]
+    fn it_refuses_payloads_that_read_as_markers() {
+        assert_eq!(
+            encode_frames(&[b"aa", b"abcd"], 4),
+            Err(FrameError::PayloadContainsMarker {
+                frame: 1,
+                marker_size: 4
+            })
+        );
+        assert_eq!(
+            encode_frames(&[b"aabbcdea"], 4),
+            Err(FrameError::PayloadContainsMarker {
+                frame: 0,
+                marker_size: 4
+            })
+        );
+        assert_eq!(
+            encode_frames(&[b"aa"], 0),
+            Err(FrameError::InvalidMarkerSize(0))
+        );
+    }
+
+    /// Whether some window of `size` bytes in `payload` is all different.
+    fn has_marker(payload: &[u8], size: usize) -> bool {
+        payload
+            .windows(size)
+            .any(|window| (0..size).all(|i| (i + 1..size).all(|j| window[i] != window[j])))
+    }
+
+    proptest! {
+        #[test]
+        fn decoding_an_encoded_stream_gives_back_the_payloads(
+            payloads in prop::collection::vec(prop::collection::vec(b'a'..b'g', 0..12), 0..8),
+        ) {
+            let payloads: Vec<&[u8]> = payloads.iter().map(Vec::as_slice).collect();
+            let encoded = encode_frames(&payloads, 4);
+
+            if let Some(frame) = payloads.iter().position(|payload| has_marker(payload, 4)) {
+                prop_assert_eq!(
+                    encoded,
+                    Err(FrameError::PayloadContainsMarker { frame, marker_size: 4 })
+                );
+                return Ok(());
+            }
+            let stream = encoded.unwrap();
+            let frames = Frames::with_marker_size(&stream, 4);
+
+            prop_assert!(frames.preamble().is_empty());
+            let decoded: Vec<&[u8]> = frames.map(|frame| frame.payload).collect();
+            prop_assert_eq!(decoded, payloads);
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+use crate::Result;
+
+/// Overrides the directory the manifest and input files are read from.
+pub const INPUT_DIR_ENV: &str = "AOC_INPUT_DIR";
+pub const MANIFEST_FILE: &str = "manifest.toml";
+pub const DEFAULT_VARIANT: &str = "puzzle";
+pub const STDIN_PATH: &str = "-";
+
+/// Where a day's input should come from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputSource {
+    /// Explicit files, in the order the solution expects. A path of `-`
+    /// reads stdin.
+    Files(Vec<PathBuf>),
+    /// A named variant from the manifest, such as `example` or `puzzle`.
+    Variant(String),
+}
+
+impl Default for InputSource {
+    fn default() -> Self {
+        InputSource::Variant(String::from(DEFAULT_VARIANT))
+    }
+}
+
+/// `manifest.toml`: day name to variant name to the files making up that
+/// variant.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct InputManifest(BTreeMap<String, BTreeMap<String, Vec<PathBuf>>>);
+
+impl InputManifest {
+    pub fn variants(&self, day: u8) -> Vec<&str> {
+        self.0
+            .get(&day_key(day))
+            .map(|variants| variants.keys().map(String::as_str).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn files(&self, day: u8, variant: &str) -> Option<&[PathBuf]> {
+        self.0
+            .get(&day_key(day))
+            .and_then(|variants| variants.get(variant))
+            .map(Vec::as_slice)
+    }
+}
+
+impl std::str::FromStr for InputManifest {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+fn day_key(day: u8) -> String {
+    format!("day{}", day)
+}
+
+pub struct InputResolver {
+    input_dir: PathBuf,
+    manifest: InputManifest,
+}
+
+impl InputResolver {
+    /// Uses `AOC_INPUT_DIR` if set, falling back to the `inputs` directory of
+    /// this crate so the runner works from any working directory.
+    pub fn from_env() -> Result<Self> {
+        let input_dir = env::var_os(INPUT_DIR_ENV)
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs"));
+        Self::from_dir(input_dir)
+    }
+
+    /// Reads `manifest.toml` from `input_dir`. A missing manifest is fine as
+    /// long as inputs are then given explicitly.
+    pub fn from_dir(input_dir: impl Into<PathBuf>) -> Result<Self> {
+        let input_dir = input_dir.into();
+        let manifest_path = input_dir.join(MANIFEST_FILE);
+        let manifest = if manifest_path.exists() {
+            read_manifest(&manifest_path)?
+        } else {
+            InputManifest::default()
+        };
+        Ok(Self {
+            input_dir,
+            manifest,
+        })
+    }
+
+    /// Reads a manifest from an explicit path; its files resolve relative to
+    /// the manifest's own directory.
+    pub fn from_manifest(manifest_path: &Path) -> Result<Self> {
+        let input_dir = manifest_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Ok(Self {
+            input_dir,
+            manifest: read_manifest(manifest_path)?,
+        })
+    }
+
+    pub fn input_dir(&self) -> &Path {
+        &self.input_dir
+    }
+
+    pub fn manifest(&self) -> &InputManifest {
+        &self.manifest
+    }
+
+    pub fn files(&self, day: u8, variant: &str) -> Result<Vec<PathBuf>> {
+        let files = self.manifest.files(day, variant).ok_or_else(|| {
+            anyhow!(
+                "No {:?} input for day {} in {}",
+                variant,
+                day,
+                self.input_dir.join(MANIFEST_FILE).display()
+            )
+        })?;
+        Ok(files.iter().map(|file| self.input_dir.join(file)).collect())
+    }
+
+    pub fn load(&self, day: u8, source: &InputSource) -> Result<String> {
+        Ok(self.load_with_files(day, source)?.content)
+    }
+
+    /// Like [`InputResolver::load`], but remembers which file each line came
+    /// from so errors can point back at it.
+    pub fn load_with_files(&self, day: u8, source: &InputSource) -> Result<LoadedInput> {
+        match source {
+            InputSource::Files(files) => read_files(files),
+            InputSource::Variant(variant) => read_files(&self.files(day, variant)?),
+        }
+    }
+}
+
+/// Loads a day's puzzle input using the default resolution rules.
+pub fn load_puzzle_input(day: u8) -> Result<String> {
+    InputResolver::from_env()?.load(day, &InputSource::default())
+}
+
+fn read_manifest(path: &Path) -> Result<InputManifest> {
+    fs::read_to_string(path)
+        .with_context(|| format!("Could not read manifest {}", path.display()))?
+        .parse()
+        .with_context(|| format!("Could not parse manifest {}", path.display()))
+}
+
+fn read_file(path: &Path) -> Result<String> {
+    if path == Path::new(STDIN_PATH) {
+        let mut content = String::new();
+        io::stdin()
+            .read_to_string(&mut content)
+            .context("Could not read stdin")?;
+        Ok(content)
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Could not read {}", path.display()))
+    }
+}
+
+/// One file of a loaded input and the line of the joined content it starts
+/// on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputFile {
+    pub path: PathBuf,
+    pub first_line: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct LoadedInput {
+    pub content: String,
+    pub files: Vec<InputFile>,
+}
+
+/// Reads each file and joins multi-file inputs with a blank line, which is
+/// how solutions such as day 5 expect to receive them.
+pub fn read_files(files: &[PathBuf]) -> Result<LoadedInput> {
+    let mut content = String::new();
+    let mut input_files = vec![];
+
+    for file in files {
+        if !input_files.is_empty() {
+            content.push_str("\n\n");
+        }
+        input_files.push(InputFile {
+            path: if file == Path::new(STDIN_PATH) {
+                PathBuf::from("<stdin>")
+            } else {
+                file.clone()
+            },
+            first_line: content.matches('\n').count() + 1,
+        });
+        content.push_str(read_file(file)?.trim_end());
+    }
+
+    Ok(LoadedInput {
+        content,
+        files: input_files,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{InputManifest, InputResolver};
+
+    #[test]
+    fn it_parses_multi_file_variants() {
+        let manifest: InputManifest = r#"
+            [day5]
+            puzzle = ["day5/stacks.csv", "day5/moves.txt"]
+            custom = ["mine.txt"]
+        "#
+        .parse()
+        .unwrap();
+
+        assert_eq!(manifest.variants(5), vec!["custom", "puzzle"]);
+        assert_eq!(
+            manifest.files(5, "puzzle").unwrap(),
+            &[
+                PathBuf::from("day5/stacks.csv"),
+                PathBuf::from("day5/moves.txt")
+            ]
+        );
+        assert!(manifest.files(6, "puzzle").is_none());
+    }
+
+    #[test]
+    fn it_resolves_files_relative_to_the_input_dir() {
+        let resolver =
+            InputResolver::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs")).unwrap();
+
+        let files = resolver.files(9, "example").unwrap();
+        assert!(files[0].ends_with("inputs/examples/day9.txt"));
+        assert!(resolver.files(9, "missing").is_err());
+    }
+}
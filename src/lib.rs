@@ -0,0 +1,58 @@
+use std::fmt::{self, Display};
+
+pub mod animate;
+pub mod bench;
+pub mod cleanup;
+pub mod compact;
+pub mod cursed_day7;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod diagnostic;
+pub mod elf_fs;
+pub mod elf_path;
+pub mod elf_report;
+pub mod elf_shell;
+pub mod elf_transcript;
+pub mod framing;
+pub mod input;
+pub mod planner;
+pub mod registry;
+pub mod verify;
+pub mod visualize;
+
+pub type Error = anyhow::Error;
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A single day of the calendar: parse the puzzle input once into a typed
+/// model, then answer both parts from that model.
+pub trait Solution {
+    type Input;
+    type Part1: Display;
+    type Part2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input>;
+
+    fn part1(input: &Self::Input) -> Result<Self::Part1>;
+
+    fn part2(input: &Self::Input) -> Result<Self::Part2>;
+}
+
+/// Returned by a part that has not been solved yet, so runners can tell it
+/// apart from a real failure.
+#[derive(Debug)]
+pub struct Unsolved;
+
+impl Display for Unsolved {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not solved yet")
+    }
+}
+
+impl std::error::Error for Unsolved {}
@@ -0,0 +1,201 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use thiserror::Error;
+
+use crate::day5::{CrateMover, ElfCrateMove, ElfCrateMoves, ElfCrateStacks};
+
+type Layout = Vec<Vec<String>>;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum PlanError {
+    #[error("the start has {start} stacks but the goal has {goal}")]
+    StackCount { start: usize, goal: usize },
+    #[error("the start and the goal don't hold the same crates")]
+    DifferentCrates,
+    #[error("the goal can't be reached with this crane")]
+    Unreachable,
+    #[error("gave up after exploring {0} states without reaching the goal")]
+    SearchLimit(usize),
+}
+
+/// Bounds on the search.
+#[derive(Debug, Clone, Copy)]
+pub struct PlanConfig {
+    /// Most states to expand before giving up.
+    pub max_states: usize,
+    /// How much to trust the heuristic. 1 finds a shortest plan; higher
+    /// values find one at most `weight` times longer, usually much faster.
+    pub weight: usize,
+}
+
+impl Default for PlanConfig {
+    fn default() -> Self {
+        Self {
+            max_states: 200_000,
+            weight: 1,
+        }
+    }
+}
+
+/// A lower bound on the moves left. Each move takes from one stack and adds
+/// to one other, so every stack with wrong crates on top needs a move from
+/// it and every stack missing crates needs a move onto it.
+fn moves_left(layout: &Layout, goal: &Layout) -> usize {
+    let (mut to_clear, mut to_fill) = (0, 0);
+    for (stack, goal_stack) in layout.iter().zip(goal.iter()) {
+        let settled = stack
+            .iter()
+            .zip(goal_stack.iter())
+            .take_while(|(label, goal_label)| label == goal_label)
+            .count();
+        if stack.len() > settled {
+            to_clear += 1;
+        }
+        if goal_stack.len() > settled {
+            to_fill += 1;
+        }
+    }
+    to_clear.max(to_fill)
+}
+
+fn sorted_crates(layout: &Layout) -> Vec<&String> {
+    let mut crates: Vec<&String> = layout.iter().flatten().collect();
+    crates.sort();
+    crates
+}
+
+/// Searches for the fewest moves that take `start` to `goal` with `crane`,
+/// using A* over every move that takes at least one crate.
+pub fn plan_moves(
+    start: &ElfCrateStacks,
+    goal: &ElfCrateStacks,
+    crane: &dyn CrateMover,
+    config: &PlanConfig,
+) -> Result<ElfCrateMoves, PlanError> {
+    let start: Layout = start.stacks().to_vec();
+    let goal: Layout = goal.stacks().to_vec();
+    if start.len() != goal.len() {
+        return Err(PlanError::StackCount {
+            start: start.len(),
+            goal: goal.len(),
+        });
+    }
+    if sorted_crates(&start) != sorted_crates(&goal) {
+        return Err(PlanError::DifferentCrates);
+    }
+
+    // Every state seen, with the state and move it was first reached by.
+    let mut nodes: Vec<(Layout, Option<(usize, ElfCrateMove)>)> = vec![(start.clone(), None)];
+    let mut best_cost: HashMap<Layout, usize> = HashMap::from([(start.clone(), 0)]);
+    let mut open = BinaryHeap::from([Reverse((moves_left(&start, &goal), 0, 0))]);
+    let mut expanded = 0;
+
+    while let Some(Reverse((_, cost, node))) = open.pop() {
+        let layout = nodes[node].0.clone();
+        if layout == goal {
+            let mut plan = vec![];
+            let mut current = node;
+            while let Some((parent, elf_move)) = nodes[current].1.clone() {
+                plan.push(elf_move);
+                current = parent;
+            }
+            plan.reverse();
+            return Ok(ElfCrateMoves::from(plan));
+        }
+        if best_cost.get(&layout).is_some_and(|&best| best < cost) {
+            continue;
+        }
+
+        expanded += 1;
+        if expanded > config.max_states {
+            return Err(PlanError::SearchLimit(config.max_states));
+        }
+
+        for from in 0..layout.len() {
+            for to in (0..layout.len()).filter(|&to| to != from) {
+                for move_num in 1..=layout[from].len() {
+                    let elf_move = ElfCrateMove::new(move_num, from + 1, to + 1);
+                    let mut next = ElfCrateStacks::from(layout.clone());
+                    crane.apply(&mut next, &elf_move);
+                    let next = next.stacks().to_vec();
+
+                    if best_cost.get(&next).is_some_and(|&best| best <= cost + 1) {
+                        continue;
+                    }
+                    best_cost.insert(next.clone(), cost + 1);
+                    let estimate = cost + 1 + config.weight * moves_left(&next, &goal);
+                    open.push(Reverse((estimate, cost + 1, nodes.len())));
+                    nodes.push((next, Some((node, elf_move))));
+                }
+            }
+        }
+    }
+
+    Err(PlanError::Unreachable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plan_moves, PlanConfig, PlanError};
+    use crate::day5::{CrateMover9000, CrateMover9001, ElfCrateMoves, ElfCrateStacks, MoveMode};
+
+    fn stacks(drawing: &str) -> ElfCrateStacks {
+        drawing.parse().unwrap()
+    }
+
+    #[test]
+    fn it_finds_a_single_batch_move() {
+        let plan = plan_moves(
+            &stacks("[B]\n[A]\n 1   2 "),
+            &stacks("    [B]\n    [A]\n 1   2 "),
+            &CrateMover9001,
+            &PlanConfig::default(),
+        )
+        .unwrap();
+
+        assert_eq!(plan.to_string(), "move 2 from 1 to 2");
+    }
+
+    #[test]
+    fn it_plans_moves_that_replay_through_day5() {
+        let start = stacks("    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ");
+        let goal = stacks("        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 ");
+        let plan = plan_moves(&start, &goal, &CrateMover9000, &PlanConfig::default()).unwrap();
+
+        let replayed: ElfCrateMoves = plan.to_string().parse().unwrap();
+        let mut stacks = start.clone();
+        stacks
+            .apply_moves(&replayed, &CrateMover9000, MoveMode::Strict)
+            .unwrap();
+        assert_eq!(stacks.stacks(), goal.stacks());
+        assert_eq!(plan.moves().len(), 3);
+    }
+
+    #[test]
+    fn it_beats_a_greedy_search() {
+        let start = stacks("        [E]\n        [C]\n[A] [D] [B]\n 1   2   3 ");
+        let goal = stacks("        [C]\n        [B]\n[E] [D] [A]\n 1   2   3 ");
+        let greedy = PlanConfig {
+            weight: 100,
+            ..PlanConfig::default()
+        };
+
+        let plan = plan_moves(&start, &goal, &CrateMover9000, &PlanConfig::default()).unwrap();
+        assert_eq!(plan.moves().len(), 4);
+        let plan = plan_moves(&start, &goal, &CrateMover9000, &greedy).unwrap();
+        assert_eq!(plan.moves().len(), 7);
+    }
+
+    #[test]
+    fn it_rejects_goals_with_other_crates() {
+        let result = plan_moves(
+            &stacks("[A] [B]\n 1   2 "),
+            &stacks("[A] [C]\n 1   2 "),
+            &CrateMover9001,
+            &PlanConfig::default(),
+        );
+
+        assert_eq!(result.unwrap_err(), PlanError::DifferentCrates);
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::bench::{time_part, time_phase, BenchConfig, DayTimings};
+use crate::visualize::{visualize_input, Visualize};
+use crate::{
+    cursed_day7::CursedDay7, day1::Day1, day2::Day2, day3::Day3, day4::Day4, day5::Day5,
+    day6::Day6, day7::Day7, day8::Day8, day9::Day9, Result, Solution,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// Object safe view of a [`Solution`], so days with different input and
+/// answer types can live side by side in the registry.
+pub trait DaySolution {
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Vec<(Part, Result<String>)>>;
+
+    /// Times parsing and each part separately, reusing one parsed model for
+    /// the parts.
+    fn bench(&self, input: &str, config: &BenchConfig) -> Result<DayTimings>;
+}
+
+struct SolutionEntry<S>(PhantomData<S>);
+
+impl<S: Solution> DaySolution for SolutionEntry<S> {
+    fn run(&self, input: &str, parts: &[Part]) -> Result<Vec<(Part, Result<String>)>> {
+        let parsed = S::parse(input)?;
+        Ok(parts
+            .iter()
+            .map(|&part| {
+                let answer = match part {
+                    Part::One => S::part1(&parsed).map(|answer| answer.to_string()),
+                    Part::Two => S::part2(&parsed).map(|answer| answer.to_string()),
+                };
+                (part, answer)
+            })
+            .collect())
+    }
+
+    fn bench(&self, input: &str, config: &BenchConfig) -> Result<DayTimings> {
+        let parse = time_phase(config, || S::parse(input))?;
+        let parsed = S::parse(input)?;
+        Ok(DayTimings {
+            parse,
+            part1: time_part(config, || S::part1(&parsed))?,
+            part2: time_part(config, || S::part2(&parsed))?,
+        })
+    }
+}
+
+pub struct RegisteredDay {
+    pub day: u8,
+    pub name: &'static str,
+    pub title: &'static str,
+    pub solution: Box<dyn DaySolution>,
+    /// Draws the parsed input, in colour when asked, for days that implement
+    /// [`Visualize`].
+    pub visualizer: Option<fn(&str, bool) -> Result<String>>,
+}
+
+impl RegisteredDay {
+    fn new<S: Solution + 'static>(day: u8, name: &'static str, title: &'static str) -> Self {
+        Self {
+            day,
+            name,
+            title,
+            solution: Box::new(SolutionEntry::<S>(PhantomData)),
+            visualizer: None,
+        }
+    }
+
+    fn visualized<S: Visualize + 'static>(
+        day: u8,
+        name: &'static str,
+        title: &'static str,
+    ) -> Self {
+        Self {
+            visualizer: Some(visualize_input::<S>),
+            ..Self::new::<S>(day, name, title)
+        }
+    }
+}
+
+pub fn registry() -> Vec<RegisteredDay> {
+    vec![
+        RegisteredDay::new::<Day1>(1, "day1", "Calorie Counting"),
+        RegisteredDay::new::<Day2>(2, "day2", "Rock Paper Scissors"),
+        RegisteredDay::new::<Day3>(3, "day3", "Rucksack Reorganization"),
+        RegisteredDay::new::<Day4>(4, "day4", "Camp Cleanup"),
+        RegisteredDay::visualized::<Day5>(5, "day5", "Supply Stacks"),
+        RegisteredDay::new::<Day6>(6, "day6", "Tuning Trouble"),
+        RegisteredDay::new::<Day7>(7, "day7", "No Space Left On Device"),
+        RegisteredDay::new::<CursedDay7>(7, "cursed_day7", "No Space Left On Device (Rc<RefCell>)"),
+        RegisteredDay::visualized::<Day8>(8, "day8", "Treetop Tree House"),
+        RegisteredDay::visualized::<Day9>(9, "day9", "Rope Bridge"),
+    ]
+}
@@ -0,0 +1,248 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::input::{InputResolver, InputSource};
+use crate::registry::{Part, RegisteredDay};
+use crate::{Result, Unsolved};
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// A recorded answer. Numeric answers can be written without quotes.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PartAnswers {
+    part1: Option<Answer>,
+    part2: Option<Answer>,
+}
+
+impl PartAnswers {
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part1.as_ref(),
+            Part::Two => self.part2.as_ref(),
+        }
+    }
+}
+
+/// `answers.toml`: solution name to input variant to the known answers.
+#[derive(Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub struct Answers(BTreeMap<String, BTreeMap<String, PartAnswers>>);
+
+impl Answers {
+    /// Reads `answers.toml` from the resolver's input directory.
+    pub fn load(resolver: &InputResolver) -> Result<Self> {
+        let path = resolver.input_dir().join(ANSWERS_FILE);
+        read_answers(&path)
+    }
+
+    pub fn get(&self, name: &str, variant: &str, part: Part) -> Option<&Answer> {
+        self.0
+            .get(name)
+            .and_then(|variants| variants.get(variant))
+            .and_then(|answers| answers.get(part))
+    }
+}
+
+impl std::str::FromStr for Answers {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+}
+
+fn read_answers(path: &Path) -> Result<Answers> {
+    fs::read_to_string(path)
+        .with_context(|| format!("Could not read answers {}", path.display()))?
+        .parse()
+        .with_context(|| format!("Could not parse answers {}", path.display()))
+}
+
+#[derive(Debug)]
+pub enum Verdict {
+    Pass,
+    Fail {
+        expected: String,
+        actual: String,
+    },
+    /// The solution produced an answer but none is recorded.
+    Missing {
+        actual: String,
+    },
+    Unsolved,
+    Error(String),
+}
+
+impl Verdict {
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Verdict::Fail { .. } | Verdict::Error(_))
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected, actual } => {
+                write!(
+                    f,
+                    "FAIL\n      - expected: {}\n      + actual:   {}",
+                    expected, actual
+                )
+            }
+            Verdict::Missing { actual } => write!(f, "missing (got {})", actual),
+            Verdict::Unsolved => write!(f, "unsolved"),
+            Verdict::Error(err) => write!(f, "ERROR: {}", err),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CaseReport {
+    pub name: &'static str,
+    pub variant: String,
+    pub part: Part,
+    pub verdict: Verdict,
+}
+
+impl fmt::Display for CaseReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:<12} {:<8} part {}: {}",
+            self.name, self.variant, self.part, self.verdict
+        )
+    }
+}
+
+fn judge(expected: Option<&Answer>, actual: Result<String>) -> Verdict {
+    match (expected, actual) {
+        (_, Err(err)) if err.is::<Unsolved>() => Verdict::Unsolved,
+        (_, Err(err)) => Verdict::Error(format!("{:#}", err)),
+        (None, Ok(actual)) => Verdict::Missing { actual },
+        (Some(expected), Ok(actual)) if expected.to_string() == actual => Verdict::Pass,
+        (Some(expected), Ok(actual)) => Verdict::Fail {
+            expected: expected.to_string(),
+            actual,
+        },
+    }
+}
+
+/// Runs one solution against every input variant listed for its day.
+pub fn verify_day(
+    entry: &RegisteredDay,
+    resolver: &InputResolver,
+    answers: &Answers,
+) -> Vec<CaseReport> {
+    let parts = [Part::One, Part::Two];
+    let mut reports = vec![];
+
+    for variant in resolver.manifest().variants(entry.day) {
+        let source = InputSource::Variant(String::from(variant));
+        let results = resolver
+            .load(entry.day, &source)
+            .and_then(|input| entry.solution.run(&input, &parts));
+
+        match results {
+            Ok(results) => {
+                for (part, actual) in results {
+                    reports.push(CaseReport {
+                        name: entry.name,
+                        variant: String::from(variant),
+                        part,
+                        verdict: judge(answers.get(entry.name, variant, part), actual),
+                    });
+                }
+            }
+            Err(err) => {
+                for part in parts {
+                    reports.push(CaseReport {
+                        name: entry.name,
+                        variant: String::from(variant),
+                        part,
+                        verdict: Verdict::Error(format!("{:#}", err)),
+                    });
+                }
+            }
+        }
+    }
+    reports
+}
+
+pub fn verify_all(
+    days: &[RegisteredDay],
+    resolver: &InputResolver,
+    answers: &Answers,
+) -> Vec<CaseReport> {
+    days.iter()
+        .flat_map(|entry| verify_day(entry, resolver, answers))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{verify_all, Answers, Verdict};
+    use crate::input::InputResolver;
+    use crate::registry::registry;
+
+    #[test]
+    fn every_solution_matches_its_golden_answers() {
+        let resolver =
+            InputResolver::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs")).unwrap();
+        let answers = Answers::load(&resolver).unwrap();
+
+        let reports = verify_all(&registry(), &resolver, &answers);
+        assert!(!reports.is_empty());
+
+        let failures: Vec<String> = reports
+            .iter()
+            .filter(|report| report.verdict.is_failure())
+            .map(|report| report.to_string())
+            .collect();
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn it_reports_missing_and_wrong_answers() {
+        let answers: Answers = r#"
+            [day1]
+            example = { part1 = 1 }
+        "#
+        .parse()
+        .unwrap();
+        let resolver =
+            InputResolver::from_dir(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs")).unwrap();
+        let day1 = registry().into_iter().next().unwrap();
+
+        let reports = super::verify_day(&day1, &resolver, &answers);
+        let example: Vec<&Verdict> = reports
+            .iter()
+            .filter(|report| report.variant == "example")
+            .map(|report| &report.verdict)
+            .collect();
+
+        assert!(matches!(example[0], Verdict::Fail { actual, .. } if actual == "24000"));
+        assert!(matches!(example[1], Verdict::Missing { actual } if actual == "45000"));
+    }
+}
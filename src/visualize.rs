@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::day5::{run_crane, CrateMover9000, CrateMover9001, Day5, ElfCrateStacks, MoveMode};
+use crate::day8::{visibility_map, Day8, ElfForest};
+use crate::day9::{rope_states, Day9, Direction, ElfRopePos};
+use crate::{Result, Solution};
+
+/// A solution that can draw its parsed input in the terminal, in colour
+/// when `color` is set.
+pub trait Visualize: Solution {
+    fn visualize(input: &Self::Input, color: bool) -> String;
+}
+
+/// Parses `input` and draws it, for use through the registry.
+pub fn visualize_input<S: Visualize>(input: &str, color: bool) -> Result<String> {
+    Ok(S::visualize(&S::parse(input)?, color))
+}
+
+/// `text` with `style` applied when `color` is set, plain otherwise.
+fn paint(text: &str, color: bool, style: impl FnOnce(ColoredString) -> ColoredString) -> String {
+    match color {
+        true => style(text.normal()).to_string(),
+        false => String::from(text),
+    }
+}
+
+/// Draws the stacks the way the puzzle does, tallest stack at the top and
+/// the stack numbers underneath.
+pub fn render_crate_stacks(stacks: &ElfCrateStacks, color: bool) -> String {
+    render_crate_stacks_highlighted(stacks, None, color)
+}
+
+/// Like [`render_crate_stacks`], but `highlight` picks out the top `count`
+/// crates of the 1-based stack it names.
+pub fn render_crate_stacks_highlighted(
+    stacks: &ElfCrateStacks,
+    highlight: Option<(usize, usize)>,
+    color: bool,
+) -> String {
+    let stacks = stacks.stacks();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+    let mut rows: Vec<String> = (0..height)
+        .rev()
+        .map(|level| {
+            stacks
+                .iter()
+                .enumerate()
+                .map(|(idx, stack)| match stack.get(level) {
+                    Some(label) => match highlight {
+                        Some((to_stack, count))
+                            if to_stack == idx + 1 && level + count >= stack.len() =>
+                        {
+                            let label =
+                                paint(label, color, |label| label.bold().black().on_green());
+                            format!("[{}]", label)
+                        }
+                        _ => format!("[{}]", paint(label, color, |label| label.bold().yellow())),
+                    },
+                    None => String::from("   "),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        })
+        .collect();
+
+    rows.push(
+        (1..=stacks.len())
+            .map(|idx| paint(&format!(" {} ", idx), color, |idx| idx.dimmed()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    );
+    rows.join("\n")
+}
+
+fn height_color(height: u32) -> Color {
+    let shade = (60 + height * 20) as u8;
+    Color::TrueColor {
+        r: shade / 4,
+        g: shade,
+        b: shade / 4,
+    }
+}
+
+/// Draws every tree's height, shaded from short (dark) to tall (bright),
+/// with the trees visible from outside the grid in bold and underlined.
+pub fn render_forest(forest: &ElfForest, color: bool) -> String {
+    let visible = visibility_map(forest);
+
+    forest
+        .heights()
+        .iter()
+        .zip(visible.iter())
+        .map(|(row, visible_row)| {
+            row.iter()
+                .zip(visible_row.iter())
+                .map(|(&height, &is_visible)| {
+                    paint(&height.to_string(), color, |tree| {
+                        let tree = tree.color(height_color(height));
+                        match is_visible {
+                            true => tree.bold().underline(),
+                            false => tree,
+                        }
+                    })
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Draws every cell the tail visited, plus the start and the final head and
+/// tail positions. Up is towards the top of the grid.
+pub fn render_rope(directions: &[Direction], color: bool) -> String {
+    let states = rope_states(directions);
+    let visited: HashSet<ElfRopePos> = states.iter().map(|state| state.tail).collect();
+    let last = states.last().copied().unwrap_or(states[0]);
+
+    let positions = states.iter().flat_map(|state| [state.head, state.tail]);
+    let (min_x, max_x, min_y, max_y) =
+        positions.fold((0, 0, 0, 0), |(min_x, max_x, min_y, max_y), pos| {
+            (
+                min_x.min(pos.x),
+                max_x.max(pos.x),
+                min_y.min(pos.y),
+                max_y.max(pos.y),
+            )
+        });
+
+    (min_x..=max_x)
+        .rev()
+        .map(|x| {
+            (min_y..=max_y)
+                .map(|y| {
+                    let pos = ElfRopePos { x, y };
+                    if pos == last.head {
+                        paint("H", color, |head| head.bold().red())
+                    } else if pos == last.tail {
+                        paint("T", color, |tail| tail.bold().blue())
+                    } else if pos == (ElfRopePos { x: 0, y: 0 }) {
+                        paint("s", color, |start| start.bold())
+                    } else if visited.contains(&pos) {
+                        paint("#", color, |visited| visited.cyan())
+                    } else {
+                        paint(".", color, |cell| cell.dimmed())
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Visualize for Day5 {
+    fn visualize((elf_stacks, elf_moves): &Self::Input, color: bool) -> String {
+        // Lenient, so a bad move list still draws something.
+        let after_moves = |crane| match run_crane(elf_stacks, elf_moves, crane, MoveMode::Lenient) {
+            Ok(stacks) => render_crate_stacks(&stacks, color),
+            Err(err) => err.to_string(),
+        };
+        format!(
+            "Starting stacks:\n{}\n\nAfter the CrateMover 9000:\n{}\n\nAfter the CrateMover 9001:\n{}",
+            render_crate_stacks(elf_stacks, color),
+            after_moves(&CrateMover9000),
+            after_moves(&CrateMover9001)
+        )
+    }
+}
+
+impl Visualize for Day8 {
+    fn visualize(input: &Self::Input, color: bool) -> String {
+        render_forest(input, color)
+    }
+}
+
+impl Visualize for Day9 {
+    fn visualize(input: &Self::Input, color: bool) -> String {
+        render_rope(input, color)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_crate_stacks, render_rope};
+    use crate::day5::ElfCrateStacks;
+    use crate::day9::Direction;
+
+    #[test]
+    fn it_draws_crate_towers() {
+        let stacks: ElfCrateStacks = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 "
+            .parse()
+            .unwrap();
+
+        assert_eq!(
+            render_crate_stacks(&stacks, false),
+            "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn it_draws_the_rope_path() {
+        let directions = [Direction::Up(2)];
+
+        assert_eq!(render_rope(&directions, false), "H\nT\ns");
+    }
+}